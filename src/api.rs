@@ -1,55 +1,183 @@
 use crate::{domain::*, services::*};
 use crate::domain::NOVAQModelCandid;
-use candid::{candid_method, CandidType, Deserialize};
+use crate::infra::{guards, metrics};
+use crate::services::governance::{GovernanceConfig, GovernanceEngine, GovernanceProposal, ProposalResults, ProposalStatus, ProposalType, Vote};
+use candid::candid_method;
 use ic_cdk::{api::caller, query, update};
-use ic_cdk_macros::{init, post_upgrade, pre_upgrade};
-use serde::Serialize;
-use std::cell::RefCell;
+use ic_cdk_macros::{heartbeat, init, post_upgrade, pre_upgrade};
+use std::cell::{Cell, RefCell};
 
 thread_local! {
     static REPOSITORY: RefCell<ModelRepository> = RefCell::new(ModelRepository::new());
+    static GOVERNANCE: RefCell<GovernanceEngine> = RefCell::new(GovernanceEngine::new());
+    // Heartbeat schedule, in nanoseconds; the last run is not persisted
+    static MAINTENANCE_INTERVAL_NS: Cell<u64> = const { Cell::new(DEFAULT_MAINTENANCE_INTERVAL_SECS * 1_000_000_000) };
+    static LAST_MAINTENANCE_RUN: Cell<u64> = const { Cell::new(0) };
 }
 
+const DEFAULT_MAINTENANCE_INTERVAL_SECS: u64 = 60 * 60;
+
+/// Semantic version of the candid interface, separate from the crate
+/// version. Bump it with every change to src/ohms_model.did.
+const INTERFACE_VERSION: &str = "2.0.0";
+
 #[init]
 fn init() {
     let admin = caller().to_text();
-    REPOSITORY.with(|repo| {
-        repo.borrow_mut().add_authorized_uploader(admin);
+    let _ = storage::grant_role(&admin, Role::Admin);
+    let _ = storage::grant_role(&admin, Role::Uploader);
+    GOVERNANCE.with(|gov| {
+        let _ = gov.borrow_mut().add_authorized_voter(admin, None);
     });
+    let _ = storage::set_schema_version(storage::SCHEMA_VERSION);
 }
 
 #[pre_upgrade]
 fn pre_upgrade() {
-    // Persist authorized uploaders list before upgrade
-    REPOSITORY.with(|repo| {
-        let repo_ref = repo.borrow();
-        let _ = crate::services::storage::set_authorized_uploaders(&repo_ref.authorized_uploaders);
-    });
+    let _ = crate::services::storage::set_metrics_snapshot(&metrics::get_metrics());
 }
 
 #[post_upgrade]
 fn post_upgrade() {
-    // Restore authorized uploaders list from stable memory
-    let uploaders = crate::services::storage::get_authorized_uploaders();
-    REPOSITORY.with(|repo| {
-        let mut r = repo.borrow_mut();
-        for u in uploaders {
-            r.add_authorized_uploader(u);
+    // Refuse to run over data written by a newer layout; trapping keeps the
+    // previous code installed
+    if let Some(version) = storage::get_schema_version() {
+        if version > storage::SCHEMA_VERSION {
+            ic_cdk::trap(&format!(
+                "Stable data has schema version {}, this build supports up to {}",
+                version, storage::SCHEMA_VERSION
+            ));
         }
+    }
+
+    // Wrap records from older layouts in the current envelope
+    if storage::get_schema_version().is_none_or(|version| version < storage::SCHEMA_VERSION) {
+        let _ = storage::migrate_storage();
+    }
+
+    // Roles live in stable memory; fold in the legacy uploader list if present
+    if let Err(e) = storage::migrate_uploaders_to_roles() {
+        ic_cdk::trap(&format!("Legacy uploader list could not be migrated: {:?}", e));
+    }
+    if let Err(e) = storage::verify_restored_roles() {
+        ic_cdk::trap(&format!("Role restore check failed: {}", e));
+    }
+    let _ = crate::services::storage::rebuild_meta_index();
+    // Manifests no longer embed the full NOVAQ model
+    let _ = crate::services::storage::strip_embedded_models();
+    REPOSITORY.with(|repo| {
+        // Rehydrate the in-memory mirror; stable storage is the source of truth
+        repo.borrow_mut().load_from_stable();
     });
+    // Governance is written through on every change; reload the mirror
+    GOVERNANCE.with(|gov| gov.borrow_mut().load_from_stable());
+    if let Some(snapshot) = crate::services::storage::get_metrics_snapshot() {
+        metrics::restore_metrics(snapshot);
+    }
+    storage::refresh_model_counts();
+    if let Some(config) = crate::services::storage::get_rate_limit_config() {
+        guards::restore_rate_limits(config);
+    }
+    if let Some(seconds) = crate::services::storage::get_maintenance_interval() {
+        MAINTENANCE_INTERVAL_NS.with(|interval| interval.set(seconds.saturating_mul(1_000_000_000)));
+    }
+    let _ = storage::set_schema_version(storage::SCHEMA_VERSION);
+}
+
+/// Run the periodic sweeps once per maintenance interval; every other
+/// round returns after a clock comparison
+#[heartbeat]
+fn heartbeat() {
+    let now = ic_cdk::api::time();
+    let interval = MAINTENANCE_INTERVAL_NS.with(Cell::get);
+    let last_run = LAST_MAINTENANCE_RUN.with(Cell::get);
+    if interval == 0 || now.saturating_sub(last_run) < interval {
+        return;
+    }
+    LAST_MAINTENANCE_RUN.with(|last| last.set(now));
+    // Writes are frozen in maintenance mode
+    if storage::get_maintenance_mode() {
+        return;
+    }
+
+    GOVERNANCE.with(|gov| gov.borrow_mut().sweep_expired_proposals(now));
+    uploads::expire_sessions(now);
+    guards::prune_rate_limit_windows(now);
+    let retention_days = storage::get_deprecation_retention();
+    if retention_days > 0 {
+        let actor = ic_cdk::api::id().to_text();
+        let _ = REPOSITORY.with(|repo| repo.borrow_mut().purge_expired_deprecated(now, retention_days, actor));
+    }
+    metrics::record_cycles_sample("heartbeat");
+}
+
+/// Count a repository failure under its `ModelError` variant and return its message
+fn record_error(error: RepositoryError) -> String {
+    metrics::increment_error(&format!("{:?}", error.kind));
+    error.into()
+}
+
+/// Count a repository failure and return its variant, for the v2 endpoints
+fn record_error_kind(error: RepositoryError) -> ModelError {
+    metrics::increment_error(&format!("{:?}", error.kind));
+    error.kind
+}
+
+/// Reject model-changing calls while an operator holds the canister in maintenance
+fn check_maintenance() -> RepositoryResult<()> {
+    if storage::get_maintenance_mode() {
+        return Err(RepositoryError::new(ModelError::Maintenance, "Canister is in maintenance mode"));
+    }
+    Ok(())
+}
+
+fn check_rate_limit() -> RepositoryResult<()> {
+    guards::check_rate_limit().map_err(|e| RepositoryError::new(ModelError::RateLimited, e))
 }
 
 // Core model operations
 #[update]
 #[candid_method(update)]
 fn submit_model(upload: ModelUpload) -> Result<String, String> {
+    submit_model_inner(upload).map_err(record_error)?;
+    
+    Ok("Model submitted successfully".to_string())
+}
+
+fn submit_model_inner(upload: ModelUpload) -> RepositoryResult<()> {
+    check_maintenance()?;
+    check_rate_limit()?;
+    metrics::increment_counter("upload_requests");
     let actor = caller().to_text();
     
     REPOSITORY.with(|repo| {
         repo.borrow_mut().submit_model(upload, actor)
     })?;
-    
-    Ok("Model submitted successfully".to_string())
+    metrics::record_cycles_sample("submit_model");
+    Ok(())
+}
+
+/// Submit and go live in one update. With governance enabled the model is
+/// submitted and an activation proposal opened instead, and its id is
+/// returned. If activation fails the call traps, so the submission is
+/// rolled back with it.
+#[update]
+#[candid_method(update)]
+fn submit_and_activate_model(upload: ModelUpload) -> Result<Option<u64>, String> {
+    let model_id = upload.manifest.model_id.clone();
+    submit_model_inner(upload).map_err(record_error)?;
+
+    // An idempotent retry of a call that already went live has nothing left to do
+    let already_active = storage::get_manifest(&model_id.0)
+        .is_ok_and(|manifest| matches!(manifest.state, ModelState::Active));
+    if already_active {
+        return Ok(None);
+    }
+
+    match activate_model_inner(model_id, None) {
+        Ok(proposal_id) => Ok(proposal_id),
+        Err(e) => ic_cdk::trap(&format!("Activation failed, submission rolled back: {}", e.message)),
+    }
 }
 
 #[update]
@@ -59,54 +187,341 @@ fn submit_quantized_model(
     source_model: String,
     quantized_model: NOVAQModelCandid,
     verification: NOVAQVerificationReport,
+    signature: Option<String>,
 ) -> Result<String, String> {
+    submit_quantized_model_inner(model_id, source_model, quantized_model, verification, signature)
+        .map_err(record_error)?;
+    
+    Ok("Quantized model submitted successfully".to_string())
+}
+
+fn submit_quantized_model_inner(
+    model_id: String,
+    source_model: String,
+    quantized_model: NOVAQModelCandid,
+    verification: NOVAQVerificationReport,
+    signature: Option<String>,
+) -> RepositoryResult<()> {
+    check_maintenance()?;
+    check_rate_limit()?;
+    metrics::increment_counter("upload_requests");
     let actor = caller().to_text();
     
+    let quantized_model: NOVAQModel = quantized_model.into();
+    validation::validate_novaq_model(&quantized_model)
+        .and_then(|_| validation::validate_quantization_indices(&quantized_model))
+        .map_err(|e| RepositoryError::new(ModelError::InvalidFormat, format!("Invalid NOVAQ model: {}", e)))?;
+    validation::validate_verification_report(&verification, &quantized_model)
+        .map_err(|e| RepositoryError::new(ModelError::InvalidFormat, format!("Invalid verification report: {}", e)))?;
+    
     // Create upload from quantized model
-    let upload = ModelUpload::from_quantized_model(
+    let mut upload = ModelUpload::from_quantized_model(
         model_id,
         source_model,
-        quantized_model.into(),
+        quantized_model,
         verification,
     );
+    upload.signature = signature;
     
     REPOSITORY.with(|repo| {
         repo.borrow_mut().submit_model(upload, actor)
     })?;
+    metrics::record_cycles_sample("submit_quantized_model");
+    Ok(())
+}
+
+// Staged multi-part uploads
+#[update]
+#[candid_method(update)]
+fn begin_upload(
+    model_id: ModelId,
+    manifest: ModelManifest,
+    meta: ModelMeta,
+    signature: Option<String>,
+) -> Result<UploadSession, String> {
+    check_maintenance().map_err(record_error)?;
+    guards::check_rate_limit()?;
+    let actor = caller().to_text();
+    ensure_role(&actor, &[Role::Uploader], "upload models")?;
     
-    Ok("Quantized model submitted successfully".to_string())
+    uploads::begin(model_id, manifest, meta, signature, actor, ic_cdk::api::time())
+}
+
+#[update]
+#[candid_method(update)]
+fn upload_chunk(session_id: String, chunk: ChunkData) -> Result<UploadSession, String> {
+    check_maintenance().map_err(record_error)?;
+    guards::check_rate_limit()?;
+    let actor = caller().to_text();
+    
+    uploads::put_chunk(&session_id, chunk, &actor, ic_cdk::api::time())
+}
+
+#[update]
+#[candid_method(update)]
+fn finish_upload(session_id: String) -> Result<String, String> {
+    check_maintenance().map_err(record_error)?;
+    guards::check_rate_limit()?;
+    metrics::increment_counter("upload_requests");
+    let actor = caller().to_text();
+    
+    let upload = uploads::assemble(&session_id, &actor, ic_cdk::api::time())?;
+    REPOSITORY.with(|repo| {
+        repo.borrow_mut().submit_model(upload, actor)
+    }).map_err(record_error)?;
+    uploads::discard(&session_id);
+    metrics::record_cycles_sample("finish_upload");
+    
+    Ok("Model submitted successfully".to_string())
+}
+
+#[update]
+#[candid_method(update)]
+fn cancel_upload(session_id: String) -> Result<String, String> {
+    let actor = caller().to_text();
+    
+    uploads::cancel(&session_id, &actor)?;
+    
+    Ok("Upload cancelled".to_string())
+}
+
+#[update]
+#[candid_method(update)]
+fn expire_upload_sessions() -> Result<u32, String> {
+    let actor = caller().to_text();
+    ensure_role(&actor, &[Role::Admin], "expire upload sessions")?;
+    
+    Ok(uploads::expire_sessions(ic_cdk::api::time()))
 }
 
 #[update]  
 #[candid_method(update)]
-fn activate_model(model_id: ModelId) -> Result<String, String> {
+fn activate_model(model_id: ModelId, serve_after: Option<u64>) -> Result<String, String> {
+    match activate_model_inner(model_id, serve_after).map_err(record_error)? {
+        Some(proposal_id) => Ok(format!("Activation proposal {} created", proposal_id)),
+        None => Ok("Model activated successfully".to_string()),
+    }
+}
+
+/// Activate directly, or with governance enabled open (or reuse) an
+/// ActivateModel proposal and return its id. `serve_after` only applies
+/// to direct activation.
+fn activate_model_inner(model_id: ModelId, serve_after: Option<u64>) -> RepositoryResult<Option<u64>> {
+    check_maintenance()?;
+    metrics::increment_counter("activation_requests");
     let actor = caller().to_text();
     
+    if REPOSITORY.with(|repo| repo.borrow().governance_enabled()) {
+        REPOSITORY.with(|repo| repo.borrow().check_activation_request(&model_id, &actor))?;
+        let proposal_id = GOVERNANCE.with(|gov| {
+            gov.borrow_mut().request_activation(model_id, actor, ic_cdk::api::time())
+        }).map_err(|e| RepositoryError::new(ModelError::InvalidState, e))?;
+        return Ok(Some(proposal_id));
+    }
+
     REPOSITORY.with(|repo| {
-        repo.borrow_mut().activate_model(&model_id, actor)
+        repo.borrow_mut().activate_model(&model_id, serve_after, actor)
     })?;
+    Ok(None)
+}
+
+#[update]
+#[candid_method(update)]
+fn set_governance_enabled(enabled: bool) -> Result<String, String> {
+    let actor = caller().to_text();
+    
+    REPOSITORY.with(|repo| {
+        repo.borrow_mut().set_governance_enabled(enabled, actor)
+    }).map_err(record_error)?;
     
-    Ok("Model activated successfully".to_string())
+    Ok(format!("Governance {}", if enabled { "enabled" } else { "disabled" }))
+}
+
+#[query]
+#[candid_method(query)]
+fn get_governance_enabled() -> bool {
+    REPOSITORY.with(|repo| repo.borrow().governance_enabled())
 }
 
 #[update]
 #[candid_method(update)]
 fn deprecate_model(model_id: ModelId) -> Result<String, String> {
+    deprecate_model_inner(model_id).map_err(record_error)?;
+    
+    Ok("Model deprecated successfully".to_string())
+}
+
+fn deprecate_model_inner(model_id: ModelId) -> RepositoryResult<()> {
+    check_maintenance()?;
+    metrics::increment_counter("deprecation_requests");
     let actor = caller().to_text();
+    check_role(&actor, &[Role::Admin], "deprecate models")?;
     
     REPOSITORY.with(|repo| {
         repo.borrow_mut().deprecate_model(&model_id, actor)
+    })
+}
+
+/// Deprecate every Active model of a family. With governance enabled a
+/// DeprecateModel proposal is opened (or reused) for each instead, and the
+/// ids returned are the models now awaiting a vote.
+#[update]
+#[candid_method(update)]
+fn deprecate_models_by_family(family: String) -> Result<Vec<ModelId>, String> {
+    deprecate_models_inner(storage::query_models_by_family(&family)).map_err(record_error)
+}
+
+/// Architecture counterpart of `deprecate_models_by_family`
+#[update]
+#[candid_method(update)]
+fn deprecate_models_by_arch(arch: String) -> Result<Vec<ModelId>, String> {
+    deprecate_models_inner(storage::query_models_by_arch(&arch)).map_err(record_error)
+}
+
+fn deprecate_models_inner(model_ids: Vec<ModelId>) -> RepositoryResult<Vec<ModelId>> {
+    check_maintenance()?;
+    metrics::increment_counter("deprecation_requests");
+    let actor = caller().to_text();
+    check_role(&actor, &[Role::Admin], "deprecate models")?;
+
+    if REPOSITORY.with(|repo| repo.borrow().governance_enabled()) {
+        let active: Vec<ModelId> = model_ids
+            .into_iter()
+            .filter(|id| storage::get_manifest(&id.0).is_ok_and(|m| matches!(m.state, ModelState::Active)))
+            .collect();
+        let now = ic_cdk::api::time();
+        GOVERNANCE.with(|gov| {
+            let mut gov = gov.borrow_mut();
+            for model_id in &active {
+                gov.request_deprecation(model_id.clone(), actor.clone(), now)?;
+            }
+            Ok::<(), String>(())
+        }).map_err(|e| RepositoryError::new(ModelError::InvalidState, e))?;
+        return Ok(active);
+    }
+
+    let deprecated = REPOSITORY.with(|repo| {
+        repo.borrow_mut().deprecate_models(&model_ids, actor)
     })?;
+    metrics::record_cycles_sample("deprecate_models");
+    Ok(deprecated)
+}
+
+#[update]
+#[candid_method(update)]
+fn delete_model(model_id: ModelId) -> Result<String, String> {
+    check_maintenance().map_err(record_error)?;
+    let actor = caller().to_text();
     
-    Ok("Model deprecated successfully".to_string())
+    REPOSITORY.with(|repo| {
+        repo.borrow_mut().delete_model(&model_id, actor)
+    }).map_err(record_error)?;
+    metrics::record_cycles_sample("delete_model");
+    
+    Ok("Model deleted successfully".to_string())
 }
 
 // Query operations
 #[query]
 #[candid_method(query)]
 fn get_manifest(model_id: ModelId) -> Option<ModelManifest> {
-    // Prefer stable storage read for source of truth
-    crate::services::storage::get_manifest(&model_id.0).ok()
+    // Reads stable storage, the source of truth. Like get_chunk, private
+    // models are not served here in audited mode.
+    REPOSITORY.with(|repo| repo.borrow().read_manifest(&model_id)).ok()
+}
+
+#[update]
+#[candid_method(update)]
+fn get_manifest_audited(model_id: ModelId) -> Result<ModelManifest, String> {
+    guards::check_rate_limit()?;
+    let actor = caller().to_text();
+    REPOSITORY.with(|repo| repo.borrow_mut().get_manifest_audited(&model_id, actor))
+        .map_err(record_error)
+}
+
+#[query]
+#[candid_method(query)]
+fn get_manifest_digest(model_id: ModelId) -> Option<String> {
+    storage::get_manifest(&model_id.0).ok().map(|manifest| manifest.digest)
+}
+
+/// Manifest without an embedded NOVAQ model, for listing and browsing.
+/// Stored manifests are already stripped on write and on upgrade, so this
+/// currently matches get_manifest, but it stays light whatever is stored.
+#[query]
+#[candid_method(query)]
+fn get_manifest_light(model_id: ModelId) -> Option<ModelManifest> {
+    storage::get_manifest(&model_id.0).ok().map(|mut manifest| {
+        manifest.strip_embedded_model();
+        manifest
+    })
+}
+
+#[query]
+#[candid_method(query)]
+fn get_chunk_count(model_id: ModelId) -> Option<u64> {
+    storage::get_manifest(&model_id.0).ok().map(|manifest| manifest.chunks.len() as u64)
+}
+
+#[query]
+#[candid_method(query)]
+fn get_chunk_info(model_id: ModelId, chunk_id: String) -> Option<ChunkInfo> {
+    storage::get_manifest(&model_id.0)
+        .ok()?
+        .chunks
+        .into_iter()
+        .find(|chunk| chunk.id == chunk_id)
+}
+
+/// Compare a client-computed digest (same scheme as
+/// `calculate_manifest_digest`) against one recomputed from the manifest
+#[query]
+#[candid_method(query)]
+fn verify_assembled_digest(model_id: ModelId, computed: String) -> bool {
+    storage::get_manifest(&model_id.0)
+        .map(|manifest| validation::calculate_manifest_digest(&manifest).eq_ignore_ascii_case(computed.trim()))
+        .unwrap_or(false)
+}
+
+/// Manifest as JSON for off-chain tooling. Legacy manifests may still hold
+/// an embedded NOVAQ model large enough to overflow a query reply, so it is
+/// only included on request.
+#[query]
+#[candid_method(query)]
+fn export_manifest_json(model_id: ModelId, include_quantized_model: bool) -> Option<String> {
+    let mut manifest = storage::get_manifest(&model_id.0).ok()?;
+    if !include_quantized_model {
+        manifest.quantized_model = None;
+    }
+    serde_json::to_string(&manifest).ok()
+}
+
+#[query]
+#[candid_method(query)]
+fn export_meta_json(model_id: ModelId) -> Option<String> {
+    let meta = storage::get_model_meta(&model_id.0).ok()?;
+    serde_json::to_string(&meta).ok()
+}
+
+/// Import a manifest (and optionally its meta) in the JSON shape produced by
+/// `export_manifest_json`/`export_meta_json`. The model is stored Pending
+/// without chunks; upload them afterwards with `replace_chunk`.
+#[update]
+#[candid_method(update)]
+fn import_manifest_json(manifest_json: String, meta_json: Option<String>) -> Result<ModelId, String> {
+    check_maintenance().map_err(record_error)?;
+    let actor = caller().to_text();
+    
+    let manifest: ModelManifest = serde_json::from_str(&manifest_json)
+        .map_err(|e| format!("Invalid manifest JSON: {}", e))?;
+    let meta: Option<ModelMeta> = meta_json
+        .map(|json| serde_json::from_str(&json))
+        .transpose()
+        .map_err(|e| format!("Invalid meta JSON: {}", e))?;
+    
+    REPOSITORY.with(|repo| {
+        repo.borrow_mut().import_manifest(manifest, meta, actor)
+    }).map_err(record_error)
 }
 
 #[query]
@@ -115,11 +530,144 @@ fn get_model_meta(model_id: ModelId) -> Option<ModelMeta> {
     crate::services::storage::get_model_meta(&model_id.0).ok()
 }
 
+/// Metas in input order, `None` for unknown ids. Only the first
+/// `MAX_META_BATCH` ids are looked up; request the rest in another call.
+#[query]
+#[candid_method(query)]
+fn batch_get_model_meta(model_ids: Vec<ModelId>) -> Vec<Option<ModelMeta>> {
+    model_ids
+        .iter()
+        .take(MAX_META_BATCH)
+        .map(|model_id| storage::get_model_meta(&model_id.0).ok())
+        .collect()
+}
+
+/// Quality figures the uploader attested to at submission
+#[query]
+#[candid_method(query)]
+fn get_quality_report(model_id: ModelId) -> Option<NOVAQVerificationReport> {
+    storage::get_quality_report(&model_id.0)
+}
+
+#[query]
+#[candid_method(query)]
+fn get_model_full(model_id: ModelId) -> Option<ModelFull> {
+    storage::get_model_full(&model_id.0)
+}
+
+#[query]
+#[candid_method(query)]
+fn get_version_history(model_id: ModelId) -> Vec<ModelManifest> {
+    storage::get_version_history(&model_id.0)
+}
+
+#[query]
+#[candid_method(query)]
+fn get_manifest_by_version(model_id: ModelId, version: String) -> Option<ModelManifest> {
+    storage::get_manifest_by_version(&model_id.0, &version)
+}
+
+#[query]
+#[candid_method(query)]
+fn find_chunk_by_hash(model_id: ModelId, sha256: String) -> Option<ChunkInfo> {
+    storage::find_chunk_by_hash(&model_id.0, &sha256)
+}
+
 #[query]
 #[candid_method(query)]
 fn get_chunk(model_id: ModelId, chunk_id: String) -> Option<Vec<u8>> {
+    // Queries cannot persist anything, so this read is never audited or
+    // counted; get_chunk_audited is the authoritative access trail and the
+    // only chunk read served in audited mode
+    if storage::get_audited_reads() {
+        return None;
+    }
+    // Counts only last for this query's replica execution; updates are the hard limit
+    guards::check_rate_limit().ok()?;
+    let actor = caller().to_text();
+    REPOSITORY.with(|repo| repo.borrow().get_chunk(&model_id, &chunk_id, &actor)).ok()
+}
+
+#[update]
+#[candid_method(update)]
+fn replace_chunk(model_id: ModelId, chunk_id: String, data: Vec<u8>) -> Result<String, String> {
+    check_maintenance().map_err(record_error)?;
+    let actor = caller().to_text();
+    
+    REPOSITORY.with(|repo| {
+        repo.borrow_mut().replace_chunk(&model_id, &chunk_id, data, actor)
+    }).map_err(record_error)?;
+    
+    Ok("Chunk replaced".to_string())
+}
+
+#[query]
+#[candid_method(query)]
+fn get_chunk_verified(model_id: ModelId, chunk_id: String) -> Result<Vec<u8>, ModelError> {
+    if storage::get_audited_reads() {
+        return Err(ModelError::InvalidState);
+    }
+    check_rate_limit().map_err(record_error_kind)?;
+    let actor = caller().to_text();
+    REPOSITORY.with(|repo| repo.borrow().get_chunk_verified(&model_id, &chunk_id, &actor))
+        .map_err(record_error_kind)
+}
+
+#[query]
+#[candid_method(query)]
+fn batch_get_chunks(model_id: ModelId, chunk_ids: Vec<String>) -> Result<Vec<Option<Vec<u8>>>, String> {
+    if storage::get_audited_reads() {
+        return Err("Audited reads are enabled; use batch_get_chunks_audited".to_string());
+    }
+    guards::check_rate_limit()?;
+    let actor = caller().to_text();
+    REPOSITORY.with(|repo| repo.borrow().batch_get_chunks(&model_id, &chunk_ids, &actor))
+        .map_err(record_error)
+}
+
+#[update]
+#[candid_method(update)]
+fn batch_get_chunks_audited(model_id: ModelId, chunk_ids: Vec<String>) -> Result<Vec<Option<Vec<u8>>>, String> {
+    guards::check_rate_limit()?;
+    let actor = caller().to_text();
+    let chunks = REPOSITORY.with(|repo| repo.borrow_mut().batch_get_chunks_audited(&model_id, &chunk_ids, actor))
+        .map_err(record_error)?;
+    metrics::increment_counter("chunk_accesses");
+    Ok(chunks)
+}
+
+#[query]
+#[candid_method(query)]
+fn get_download_plan(model_id: ModelId) -> Result<Vec<ChunkInfo>, String> {
+    let actor = caller().to_text();
+    REPOSITORY.with(|repo| repo.borrow().get_download_plan(&model_id, &actor))
+        .map_err(record_error)
+}
+
+#[query]
+#[candid_method(query)]
+fn get_chunk_range(model_id: ModelId, offset: u64, length: u64) -> Result<Vec<u8>, String> {
+    if storage::get_audited_reads() {
+        return Err("Audited reads are enabled; use get_chunk_audited".to_string());
+    }
+    guards::check_rate_limit()?;
+    let actor = caller().to_text();
+    REPOSITORY.with(|repo| repo.borrow().get_chunk_range(&model_id, offset, length, &actor))
+        .map_err(record_error)
+}
+
+#[update]
+#[candid_method(update)]
+fn get_chunk_audited(model_id: ModelId, chunk_id: String) -> Result<Vec<u8>, String> {
+    get_chunk_audited_inner(model_id, chunk_id).map_err(record_error)
+}
+
+fn get_chunk_audited_inner(model_id: ModelId, chunk_id: String) -> RepositoryResult<Vec<u8>> {
+    check_rate_limit()?;
     let actor = caller().to_text();
-    REPOSITORY.with(|repo| repo.borrow_mut().get_chunk(&model_id, &chunk_id, actor))
+    let data = REPOSITORY.with(|repo| repo.borrow_mut().get_chunk_audited(&model_id, &chunk_id, actor))?;
+    metrics::increment_counter("chunk_accesses");
+    Ok(data)
 }
 
 #[query]
@@ -141,6 +689,12 @@ fn list_models(state_filter: Option<ModelState>) -> Vec<ModelManifest> {
     out
 }
 
+#[query]
+#[candid_method(query)]
+fn get_pending_queue() -> Vec<PendingModelSummary> {
+    REPOSITORY.with(|repo| repo.borrow().pending_queue(ic_cdk::api::time()))
+}
+
 #[query]
 #[candid_method(query)]
 fn list_quantized_models() -> Vec<ModelManifest> {
@@ -165,68 +719,973 @@ fn query_models_by_size(max_size_mb: f32) -> Vec<String> {
 
 #[query]
 #[candid_method(query)]
-fn get_global_stats() -> ModelStats {
-    storage::get_global_stats().unwrap_or(ModelStats {
-        total_models: 0,
-        quantized_models: 0,
-        total_size_saved_gb: 0.0,
-        total_energy_saved: 0.0,
-        average_compression_ratio: 0.0,
+fn query_models_by_family(family: String) -> Vec<ModelId> {
+    storage::query_models_by_family(&family)
+}
+
+/// Models the caller has submitted
+#[query]
+#[candid_method(query)]
+fn list_my_models() -> Vec<ModelManifest> {
+    storage::list_models_by_uploader(&caller().to_text())
+}
+
+#[query]
+#[candid_method(query)]
+fn get_active_model_for_family(family: String) -> Option<ModelManifest> {
+    storage::get_active_model_for_family(&family)
+}
+
+#[query]
+#[candid_method(query)]
+fn query_models_by_arch(arch: String) -> Vec<ModelId> {
+    storage::query_models_by_arch(&arch)
+}
+
+#[query]
+#[candid_method(query)]
+fn query_models_by_badge(badge_type: BadgeType) -> Vec<ModelId> {
+    storage::query_models_by_badge(&badge_type)
+}
+
+#[query]
+#[candid_method(query)]
+fn query_models_by_tokenizer(tokenizer_id: String) -> Vec<ModelId> {
+    storage::query_models_by_tokenizer(&tokenizer_id)
+}
+
+#[query]
+#[candid_method(query)]
+fn query_models(query: ModelQuery) -> Vec<ModelManifest> {
+    storage::query_models(&query)
+}
+
+#[query]
+#[candid_method(query)]
+fn get_global_stats() -> ModelStats {
+    storage::get_global_stats().unwrap_or(ModelStats {
+        total_models: 0,
+        quantized_models: 0,
+        total_size_saved_gb: 0.0,
+        total_energy_saved: 0.0,
+        average_compression_ratio: 0.0,
         average_capability_retention: 0.0,
     })
 }
 
-// Audit operations
+#[update]
+#[candid_method(update)]
+fn recompute_stats() -> Result<ModelStats, String> {
+    let actor = caller().to_text();
+    ensure_role(&actor, &[Role::Admin], "recompute stats")?;
+    
+    storage::recompute_stats()
+        .map_err(|e| format!("Persist failed: {:?}", e))
+}
+
+#[query]
+#[candid_method(query)]
+fn get_stats_by_compression_type() -> Vec<CompressionTypeStats> {
+    storage::get_stats_by_compression_type()
+}
+
+#[query]
+#[candid_method(query)]
+fn find_size_inconsistencies() -> Vec<(String, String)> {
+    storage::find_size_inconsistencies()
+}
+
+// Integrity sweep
+#[update]
+#[candid_method(update)]
+fn start_integrity_sweep() -> Result<SweepStatus, String> {
+    let actor = caller().to_text();
+    ensure_role(&actor, &[Role::Admin, Role::Auditor], "run integrity sweeps")?;
+    
+    integrity::start_sweep(ic_cdk::api::time())
+        .map_err(|e| format!("Sweep start failed: {:?}", e))
+}
+
+#[update]
+#[candid_method(update)]
+fn continue_integrity_sweep(budget: u32) -> Result<SweepStatus, String> {
+    let actor = caller().to_text();
+    ensure_role(&actor, &[Role::Admin, Role::Auditor], "run integrity sweeps")?;
+    
+    if budget == 0 {
+        return Err("Budget must be greater than 0".to_string());
+    }
+    
+    integrity::continue_sweep(budget, ic_cdk::api::time())
+        .map_err(|e| format!("Sweep failed: {:?}", e))
+}
+
+#[query]
+#[candid_method(query)]
+fn get_sweep_status() -> Option<SweepStatus> {
+    storage::get_sweep_status()
+}
+
+#[query]
+#[candid_method(query)]
+fn get_access_concentration() -> f32 {
+    storage::get_access_concentration()
+}
+
+/// Chunk fetches counted for a model. Only audited reads are counted;
+/// query reads cannot persist a counter.
+#[query]
+#[candid_method(query)]
+fn get_model_popularity(model_id: ModelId) -> u64 {
+    storage::get_access_count(&model_id.0)
+}
+
+#[query]
+#[candid_method(query)]
+fn list_top_models(n: u64) -> Vec<(ModelId, u64)> {
+    storage::list_top_accessed(n as usize)
+}
+
+#[query]
+#[candid_method(query)]
+fn get_metrics() -> metrics::Metrics {
+    metrics::get_metrics()
+}
+
+#[update]
+#[candid_method(update)]
+fn refresh_model_counts() -> Result<metrics::Metrics, String> {
+    let actor = caller().to_text();
+    ensure_role(&actor, &[Role::Admin], "refresh model counts")?;
+    
+    storage::refresh_model_counts();
+    Ok(metrics::get_metrics())
+}
+
+// Audit operations
+#[query]
+#[candid_method(query)]
+fn get_audit_log() -> Vec<AuditEvent> {
+    // Stable storage is the source of truth for the audit trail
+    storage::get_audit_log()
+}
+
+#[query]
+#[candid_method(query)]
+fn query_audit_log(filter: AuditFilter) -> Vec<AuditEvent> {
+    storage::query_audit_log(&filter)
+}
+
+/// Audit events in `[from, to]` as NDJSON for log pipelines, one page per
+/// call; pass the returned cursor back until it is None
+#[query]
+#[candid_method(query)]
+fn export_audit_ndjson(from: u64, to: u64, cursor: Option<u64>) -> AuditExport {
+    storage::export_audit_ndjson(from, to, cursor.unwrap_or(0), MAX_EXPORT_BYTES)
+}
+
+// Benchmark operations
+#[update]
+#[candid_method(update)]
+fn record_benchmark(model_id: ModelId, metric: String, value: f32) -> Result<String, String> {
+    check_maintenance().map_err(record_error)?;
+    let actor = caller().to_text();
+    
+    REPOSITORY.with(|repo| {
+        repo.borrow_mut().record_benchmark(&model_id, metric, value, actor)
+    }).map_err(record_error)?;
+    
+    Ok("Benchmark recorded".to_string())
+}
+
+#[query]
+#[candid_method(query)]
+fn get_benchmarks(model_id: ModelId) -> Vec<BenchmarkResult> {
+    storage::get_benchmarks(&model_id.0)
+}
+
+#[update]
+#[candid_method(update)]
+fn set_activation_gates(gates: Vec<ActivationGate>) -> Result<String, String> {
+    let actor = caller().to_text();
+    ensure_role(&actor, &[Role::Admin], "configure activation gates")?;
+    
+    storage::set_activation_gates(&gates)
+        .map_err(|e| format!("Persist failed: {:?}", e))?;
+    
+    Ok(format!("{} activation gates configured", gates.len()))
+}
+
+#[query]
+#[candid_method(query)]
+fn get_activation_gates() -> Vec<ActivationGate> {
+    storage::get_activation_gates()
+}
+
+#[update]
+#[candid_method(update)]
+fn verify_model(model_id: ModelId) -> Result<StructureReport, String> {
+    check_maintenance().map_err(record_error)?;
+    let actor = caller().to_text();
+    ensure_role(&actor, &[Role::Admin, Role::Auditor], "verify models")?;
+    
+    REPOSITORY.with(|repo| repo.borrow_mut().verify_model(&model_id, actor))
+        .map_err(record_error)
+}
+
+#[query]
+#[candid_method(query)]
+fn get_verification(model_id: ModelId) -> Option<VerificationRecord> {
+    storage::get_verification(&model_id.0)
+}
+
+#[update]
+#[candid_method(update)]
+fn check_reproducible(model_id_a: ModelId, model_id_b: ModelId) -> Result<ReproducibilityReport, String> {
+    check_maintenance().map_err(record_error)?;
+    let actor = caller().to_text();
+    ensure_role(&actor, &[Role::Admin, Role::Auditor], "check reproducibility")?;
+    
+    REPOSITORY.with(|repo| repo.borrow_mut().check_reproducible(&model_id_a, &model_id_b, actor))
+        .map_err(record_error)
+}
+
+#[query]
+#[candid_method(query)]
+fn get_reproducibility(model_id: ModelId) -> Option<ReproducibilityReport> {
+    storage::get_reproducibility(&model_id.0)
+}
+
+// Tag operations
+#[update]
+#[candid_method(update)]
+fn add_model_tags(model_id: ModelId, tags: Vec<String>) -> Result<Vec<String>, String> {
+    check_maintenance().map_err(record_error)?;
+    let actor = caller().to_text();
+    
+    REPOSITORY.with(|repo| repo.borrow_mut().add_tags(&model_id, tags, actor))
+        .map_err(record_error)
+}
+
+#[update]
+#[candid_method(update)]
+fn remove_model_tags(model_id: ModelId, tags: Vec<String>) -> Result<Vec<String>, String> {
+    check_maintenance().map_err(record_error)?;
+    let actor = caller().to_text();
+    
+    REPOSITORY.with(|repo| repo.borrow_mut().remove_tags(&model_id, tags, actor))
+        .map_err(record_error)
+}
+
+#[query]
+#[candid_method(query)]
+fn get_model_tags(model_id: ModelId) -> Vec<String> {
+    storage::get_model_tags(&model_id.0)
+}
+
+#[query]
+#[candid_method(query)]
+fn query_models_by_tag(tag: String) -> Vec<ModelId> {
+    storage::query_models_by_tag(&tag)
+}
+
+// Badge operations
+#[update]
+#[candid_method(update)]
+fn grant_badge(model_id: ModelId, badge_type: BadgeType, metadata: Option<String>) -> Result<String, String> {
+    check_maintenance().map_err(record_error)?;
+    let actor = caller().to_text();
+    
+    REPOSITORY.with(|repo| {
+        repo.borrow_mut().grant_badge(&model_id, badge_type, metadata, actor)
+    }).map_err(record_error)?;
+    
+    Ok("Badge granted".to_string())
+}
+
+#[update]
+#[candid_method(update)]
+fn revoke_badge(model_id: ModelId, badge_type: BadgeType) -> Result<String, String> {
+    check_maintenance().map_err(record_error)?;
+    let actor = caller().to_text();
+    
+    REPOSITORY.with(|repo| {
+        repo.borrow_mut().revoke_badge(&model_id, badge_type, actor)
+    }).map_err(record_error)?;
+    
+    Ok("Badge revoked".to_string())
+}
+
+#[query]
+#[candid_method(query)]
+fn get_badges(model_id: ModelId) -> Vec<Badge> {
+    storage::get_badges(&model_id.0)
+}
+
+// Private models
+#[update]
+#[candid_method(update)]
+fn set_model_access_list(model_id: ModelId, access_list: Option<Vec<String>>) -> Result<String, String> {
+    check_maintenance().map_err(record_error)?;
+    let actor = caller().to_text();
+    
+    REPOSITORY.with(|repo| {
+        repo.borrow_mut().set_access_list(&model_id, access_list, actor)
+    }).map_err(record_error)?;
+    
+    Ok("Access list updated".to_string())
+}
+
+#[query]
+#[candid_method(query)]
+fn get_model_access_list(model_id: ModelId) -> Result<Option<Vec<String>>, String> {
+    let actor = caller().to_text();
+    ensure_role(&actor, &[Role::Admin, Role::Auditor], "view access lists")?;
+    
+    Ok(storage::get_access_list(&model_id.0))
+}
+
+/// Override a principal's upload quota; None leaves that limit unbounded
+#[update]
+#[candid_method(update)]
+fn set_upload_quota(principal: String, max_models: Option<u64>, max_bytes: Option<u64>) -> Result<String, String> {
+    let actor = caller().to_text();
+    let quota = UploadQuota { max_models, max_bytes };
+    
+    REPOSITORY.with(|repo| {
+        repo.borrow_mut().set_upload_quota(&principal, Some(quota), actor)
+    }).map_err(record_error)?;
+    
+    Ok(format!("Upload quota set for {}", principal))
+}
+
+/// Drop a principal's override so the default quota applies again
+#[update]
+#[candid_method(update)]
+fn clear_upload_quota(principal: String) -> Result<String, String> {
+    let actor = caller().to_text();
+    
+    REPOSITORY.with(|repo| {
+        repo.borrow_mut().set_upload_quota(&principal, None, actor)
+    }).map_err(record_error)?;
+    
+    Ok(format!("Upload quota override cleared for {}", principal))
+}
+
+#[update]
+#[candid_method(update)]
+fn set_default_upload_quota(max_models: Option<u64>, max_bytes: Option<u64>) -> Result<String, String> {
+    let actor = caller().to_text();
+    ensure_role(&actor, &[Role::Admin], "configure upload quotas")?;
+    
+    storage::set_default_upload_quota(&UploadQuota { max_models, max_bytes })
+        .map_err(|e| format!("Persist failed: {:?}", e))?;
+    
+    Ok("Default upload quota updated".to_string())
+}
+
+#[query]
+#[candid_method(query)]
+fn get_upload_usage(principal: String) -> UploadUsage {
+    REPOSITORY.with(|repo| repo.borrow().upload_usage(&principal))
+}
+
+// Governance operations
+#[update]
+#[candid_method(update)]
+fn create_proposal(
+    proposal_type: ProposalType,
+    model_id: ModelId,
+    description: String,
+) -> Result<u64, String> {
+    let actor = caller().to_text();
+    
+    GOVERNANCE.with(|gov| {
+        gov.borrow_mut().create_proposal(proposal_type, model_id, actor, description, ic_cdk::api::time())
+    })
+}
+
+#[update]
+#[candid_method(update)]
+fn cast_vote(proposal_id: u64, vote: Vote) -> Result<String, String> {
+    let actor = caller().to_text();
+    
+    GOVERNANCE.with(|gov| {
+        gov.borrow_mut().cast_vote(proposal_id, actor, vote, ic_cdk::api::time())
+    })?;
+    
+    Ok("Vote recorded".to_string())
+}
+
+/// Withdraw the caller's vote on an open proposal
+#[update]
+#[candid_method(update)]
+fn retract_vote(proposal_id: u64) -> Result<String, String> {
+    let actor = caller().to_text();
+    
+    GOVERNANCE.with(|gov| {
+        gov.borrow_mut().retract_vote(proposal_id, &actor, ic_cdk::api::time())
+    })?;
+    
+    Ok("Vote retracted".to_string())
+}
+
+#[update]
+#[candid_method(update)]
+fn tally_votes(proposal_id: u64) -> Result<ProposalStatus, String> {
+    GOVERNANCE.with(|gov| {
+        gov.borrow_mut().tally_votes(proposal_id, ic_cdk::api::time())
+    })
+}
+
+/// Decide open proposals past their deadline; safe for anyone to call
+#[update]
+#[candid_method(update)]
+fn sweep_expired_proposals() -> Vec<u64> {
+    GOVERNANCE.with(|gov| {
+        gov.borrow_mut().sweep_expired_proposals(ic_cdk::api::time())
+    })
+}
+
+#[update]
+#[candid_method(update)]
+fn execute_proposal(proposal_id: u64) -> Result<String, String> {
+    check_maintenance().map_err(record_error)?;
+    let actor = crate::infra::require_authenticated()?;
+    
+    // Apply the state change first so a failed action leaves the proposal executable
+    let action = GOVERNANCE.with(|gov| gov.borrow().execution_action(proposal_id))?;
+    REPOSITORY.with(|repo| {
+        repo.borrow_mut().apply_governance_action(action, actor)
+    }).map_err(record_error)?;
+    GOVERNANCE.with(|gov| gov.borrow_mut().execute_proposal(proposal_id))?;
+    
+    Ok("Proposal executed".to_string())
+}
+
+#[query]
+#[candid_method(query)]
+fn get_proposal(proposal_id: u64) -> Option<GovernanceProposal> {
+    GOVERNANCE.with(|gov| gov.borrow().get_proposal(proposal_id).cloned())
+}
+
+#[query]
+#[candid_method(query)]
+fn get_proposal_results(proposal_id: u64) -> Option<ProposalResults> {
+    GOVERNANCE.with(|gov| gov.borrow().get_results(proposal_id))
+}
+
+#[query]
+#[candid_method(query)]
+fn list_proposals() -> Vec<GovernanceProposal> {
+    GOVERNANCE.with(|gov| {
+        gov.borrow().list_proposals().into_iter().cloned().collect()
+    })
+}
+
+#[query]
+#[candid_method(query)]
+fn list_proposals_by_status(status: ProposalStatus) -> Vec<GovernanceProposal> {
+    GOVERNANCE.with(|gov| {
+        gov.borrow().list_proposals_by_status(&status).into_iter().cloned().collect()
+    })
+}
+
+#[query]
+#[candid_method(query)]
+fn list_proposals_for_model(model_id: ModelId) -> Vec<GovernanceProposal> {
+    GOVERNANCE.with(|gov| {
+        gov.borrow().list_proposals_for_model(&model_id).into_iter().cloned().collect()
+    })
+}
+
+#[update]
+#[candid_method(update)]
+fn add_authorized_voter(voter: String, weight: Option<u32>) -> Result<String, String> {
+    let actor = caller().to_text();
+    ensure_role(&actor, &[Role::Admin], "add voters")?;
+    
+    GOVERNANCE.with(|gov| {
+        gov.borrow_mut().add_authorized_voter(voter, weight)
+    })?;
+    
+    Ok("Authorized voter added".to_string())
+}
+
+#[update]
+#[candid_method(update)]
+fn set_governance_config(config: GovernanceConfig) -> Result<String, String> {
+    let actor = caller().to_text();
+    ensure_role(&actor, &[Role::Admin], "configure governance")?;
+    
+    GOVERNANCE.with(|gov| gov.borrow_mut().set_config(config))?;
+    
+    Ok("Governance config updated".to_string())
+}
+
 #[query]
 #[candid_method(query)]
-fn get_audit_log() -> Vec<AuditEvent> {
-    REPOSITORY.with(|repo| {
-        repo.borrow().get_audit_log().to_vec()
-    })
+fn get_governance_config() -> GovernanceConfig {
+    GOVERNANCE.with(|gov| gov.borrow().config().clone())
+}
+
+// Structured-error endpoints: same behavior as their v1 counterparts, but
+// failures are a `ModelError` variant instead of a message
+#[update]
+#[candid_method(update)]
+fn submit_model_v2(upload: ModelUpload) -> Result<(), ModelError> {
+    submit_model_inner(upload).map_err(record_error_kind)
+}
+
+#[update]
+#[candid_method(update)]
+fn submit_quantized_model_v2(
+    model_id: String,
+    source_model: String,
+    quantized_model: NOVAQModelCandid,
+    verification: NOVAQVerificationReport,
+    signature: Option<String>,
+) -> Result<(), ModelError> {
+    submit_quantized_model_inner(model_id, source_model, quantized_model, verification, signature)
+        .map_err(record_error_kind)
+}
+
+/// Inter-canister submission for quantizer canisters. The caller must be a
+/// canister holding the Quantizer role, and like any uploader also hold
+/// Uploader with a registered signing key. Errors are `ModelError`
+/// variants so the calling canister can branch on them.
+#[update]
+#[candid_method(update)]
+fn submit_quantized_model_ic(
+    model_id: String,
+    source_model: String,
+    quantized_model: NOVAQModelCandid,
+    verification: NOVAQVerificationReport,
+    signature: Option<String>,
+) -> Result<QuantizerSubmission, ModelError> {
+    let quantizer = caller();
+    if !crate::infra::is_canister_principal(&quantizer) {
+        return Err(record_error_kind(RepositoryError::new(
+            ModelError::UnauthorizedAccess,
+            "Caller is not a canister",
+        )));
+    }
+    check_role(&quantizer.to_text(), &[Role::Quantizer], "submit as a quantizer canister")
+        .map_err(record_error_kind)?;
+
+    submit_quantized_model_inner(model_id.clone(), source_model, quantized_model, verification, signature)
+        .map_err(record_error_kind)?;
+    let manifest = storage::get_manifest(&model_id).map_err(|_| ModelError::NotFound)?;
+    Ok(QuantizerSubmission { model_id: manifest.model_id, digest: manifest.digest })
+}
+
+#[update]
+#[candid_method(update)]
+fn activate_model_v2(model_id: ModelId, serve_after: Option<u64>) -> Result<Option<u64>, ModelError> {
+    activate_model_inner(model_id, serve_after).map_err(record_error_kind)
+}
+
+#[update]
+#[candid_method(update)]
+fn deprecate_model_v2(model_id: ModelId) -> Result<(), ModelError> {
+    deprecate_model_inner(model_id).map_err(record_error_kind)
+}
+
+#[update]
+#[candid_method(update)]
+fn get_chunk_audited_v2(model_id: ModelId, chunk_id: String) -> Result<Vec<u8>, ModelError> {
+    get_chunk_audited_inner(model_id, chunk_id).map_err(record_error_kind)
+}
+
+#[update]
+#[candid_method(update)]
+fn record_benchmark_v2(model_id: ModelId, metric: String, value: f32) -> Result<(), ModelError> {
+    check_maintenance().map_err(record_error_kind)?;
+    let actor = caller().to_text();
+    REPOSITORY.with(|repo| repo.borrow_mut().record_benchmark(&model_id, metric, value, actor))
+        .map_err(record_error_kind)
+}
+
+#[update]
+#[candid_method(update)]
+fn grant_badge_v2(model_id: ModelId, badge_type: BadgeType, metadata: Option<String>) -> Result<(), ModelError> {
+    check_maintenance().map_err(record_error_kind)?;
+    let actor = caller().to_text();
+    REPOSITORY.with(|repo| repo.borrow_mut().grant_badge(&model_id, badge_type, metadata, actor))
+        .map_err(record_error_kind)
+}
+
+#[update]
+#[candid_method(update)]
+fn revoke_badge_v2(model_id: ModelId, badge_type: BadgeType) -> Result<(), ModelError> {
+    check_maintenance().map_err(record_error_kind)?;
+    let actor = caller().to_text();
+    REPOSITORY.with(|repo| repo.borrow_mut().revoke_badge(&model_id, badge_type, actor))
+        .map_err(record_error_kind)
 }
 
 // Admin operations
+/// Require the caller to hold at least one of `roles`
+fn ensure_role(actor: &str, roles: &[Role], action: &str) -> Result<(), String> {
+    check_role(actor, roles, action).map_err(record_error)
+}
+
+fn check_role(actor: &str, roles: &[Role], action: &str) -> RepositoryResult<()> {
+    if !roles.iter().any(|role| storage::has_role(actor, role)) {
+        return Err(RepositoryError::new(ModelError::UnauthorizedAccess, format!("Not authorized to {}", action)));
+    }
+    Ok(())
+}
+
 #[update]
 #[candid_method(update)]
-fn add_authorized_uploader(uploader: String) -> Result<String, String> {
+fn grant_role(principal: String, role: Role) -> Result<String, String> {
     let actor = caller().to_text();
+    ensure_role(&actor, &[Role::Admin], "grant roles")?;
     
-    REPOSITORY.with(|repo| {
-        let repo_ref = repo.borrow();
-        if !repo_ref.authorized_uploaders.contains(&actor) {
-            return Err("Not authorized to add uploaders".to_string());
-        }
-        Ok(())
-    })?;
+    storage::grant_role(&principal, role.clone())
+        .map_err(|e| format!("Persist failed: {:?}", e))?;
+    
+    Ok(format!("Role {:?} granted", role))
+}
+
+#[update]
+#[candid_method(update)]
+fn revoke_role(principal: String, role: Role) -> Result<String, String> {
+    let actor = caller().to_text();
+    ensure_role(&actor, &[Role::Admin], "revoke roles")?;
+    
+    // Keep at least one admin so the canister cannot lock itself out
+    if role == Role::Admin && storage::list_principals_with_role(&Role::Admin) == vec![principal.clone()] {
+        return Err("Cannot revoke the last admin".to_string());
+    }
+    
+    let revoked = storage::revoke_role(&principal, &role)
+        .map_err(|e| format!("Persist failed: {:?}", e))?;
+    if !revoked {
+        return Err(format!("Principal does not hold role {:?}", role));
+    }
+    
+    Ok(format!("Role {:?} revoked", role))
+}
+
+#[query]
+#[candid_method(query)]
+fn get_roles(principal: String) -> Vec<Role> {
+    storage::get_roles(&principal)
+}
+
+#[update]
+#[candid_method(update)]
+fn remove_authorized_uploader(principal: String) -> Result<String, String> {
+    let actor = caller().to_text();
     
     REPOSITORY.with(|repo| {
-        repo.borrow_mut().add_authorized_uploader(uploader);
-    });
+        repo.borrow_mut().remove_uploader(&principal, actor)
+    }).map_err(record_error)?;
     
-    Ok("Authorized uploader added".to_string())
+    Ok("Authorized uploader removed".to_string())
 }
 
 #[update]
 #[candid_method(update)]
-fn cleanup_deprecated_models() -> Result<String, String> {
+fn register_uploader_key(principal: String, public_key: Vec<u8>) -> Result<String, String> {
     let actor = caller().to_text();
     
-    // Check authorization
     REPOSITORY.with(|repo| {
-        let repo_ref = repo.borrow();
-        if !repo_ref.authorized_uploaders.contains(&actor) {
-            return Err("Not authorized to cleanup models".to_string());
-        }
-        Ok(())
-    })?;
+        repo.borrow_mut().register_uploader_key(principal, public_key, actor)
+    }).map_err(record_error)?;
+    
+    Ok("Uploader key registered".to_string())
+}
+
+#[update]
+#[candid_method(update)]
+fn revalidate_signatures() -> Result<Vec<(String, bool)>, String> {
+    let actor = caller().to_text();
+    ensure_role(&actor, &[Role::Admin, Role::Auditor], "revalidate signatures")?;
+    
+    Ok(REPOSITORY.with(|repo| repo.borrow().revalidate_signatures()))
+}
+
+#[query]
+#[candid_method(query)]
+fn get_repository_state() -> Result<RepoStateReport, String> {
+    let actor = caller().to_text();
+    ensure_role(&actor, &[Role::Admin], "inspect repository state")?;
+    
+    Ok(REPOSITORY.with(|repo| repo.borrow().repository_state()))
+}
+
+/// Rebuild the in-memory model mirror and secondary indexes from stable storage
+#[update]
+#[candid_method(update)]
+fn resync_from_stable() -> Result<ResyncReport, String> {
+    let actor = caller().to_text();
+    
+    let report = REPOSITORY.with(|repo| repo.borrow_mut().resync_from_stable(&actor))
+        .map_err(record_error)?;
+    storage::rebuild_meta_index()
+        .map_err(|e| format!("Index rebuild failed: {:?}", e))?;
+    storage::refresh_model_counts();
+    
+    Ok(report)
+}
+
+#[update]
+#[candid_method(update)]
+fn set_audited_reads(enabled: bool) -> Result<String, String> {
+    let actor = caller().to_text();
+    ensure_role(&actor, &[Role::Admin], "configure audited reads")?;
+    
+    storage::set_audited_reads(enabled)
+        .map_err(|e| format!("Persist failed: {:?}", e))?;
+    
+    Ok(format!("Audited reads {}", if enabled { "enabled" } else { "disabled" }))
+}
+
+#[query]
+#[candid_method(query)]
+fn get_audited_reads() -> bool {
+    storage::get_audited_reads()
+}
+
+#[update]
+#[candid_method(update)]
+fn set_audit_sampling(rate: u32) -> Result<String, String> {
+    let actor = caller().to_text();
+    ensure_role(&actor, &[Role::Admin], "configure audit sampling")?;
+    if rate == 0 {
+        return Err("Sampling rate must be at least 1".to_string());
+    }
+    
+    storage::set_audit_sampling(rate)
+        .map_err(|e| format!("Persist failed: {:?}", e))?;
+    
+    Ok(format!("Logging 1 in {} chunk accesses", rate))
+}
+
+#[query]
+#[candid_method(query)]
+fn get_audit_sampling() -> u32 {
+    storage::get_audit_sampling()
+}
+
+#[update]
+#[candid_method(update)]
+fn set_maintenance_mode(enabled: bool) -> Result<String, String> {
+    let actor = caller().to_text();
+    ensure_role(&actor, &[Role::Admin], "configure maintenance mode")?;
+    
+    storage::set_maintenance_mode(enabled)
+        .map_err(|e| format!("Persist failed: {:?}", e))?;
+    
+    Ok(format!("Maintenance mode {}", if enabled { "enabled" } else { "disabled" }))
+}
+
+#[query]
+#[candid_method(query)]
+fn get_maintenance_mode() -> bool {
+    storage::get_maintenance_mode()
+}
+
+#[query]
+#[candid_method(query)]
+fn estimate_energy_savings(model_id: ModelId) -> Option<EnergyReport> {
+    storage::estimate_energy_savings(&model_id.0)
+}
+
+/// Coefficient behind every energy figure, in kWh per GB of size saved
+#[update]
+#[candid_method(update)]
+fn set_energy_per_gb(kwh: f32) -> Result<String, String> {
+    let actor = caller().to_text();
+    ensure_role(&actor, &[Role::Admin], "configure the energy coefficient")?;
+    
+    storage::set_energy_per_gb(kwh)
+        .map_err(|_| "Energy coefficient must be finite and non-negative".to_string())?;
+    
+    Ok(format!("Energy coefficient set to {} kWh/GB", kwh))
+}
+
+#[query]
+#[candid_method(query)]
+fn get_energy_per_gb() -> f32 {
+    storage::get_energy_per_gb()
+}
+
+#[update]
+#[candid_method(update)]
+fn set_min_compression_ratio(ratio: f32) -> Result<String, String> {
+    let actor = caller().to_text();
+    ensure_role(&actor, &[Role::Admin], "configure the compression floor")?;
+    
+    storage::set_min_compression_ratio(ratio)
+        .map_err(|_| "Minimum compression ratio must be finite and at least 1.0".to_string())?;
+    
+    Ok(format!("Submissions must now compress by more than {}x", ratio))
+}
+
+#[query]
+#[candid_method(query)]
+fn get_min_compression_ratio() -> f32 {
+    storage::get_min_compression_ratio()
+}
+
+#[update]
+#[candid_method(update)]
+fn set_high_compression_threshold(ratio: f32) -> Result<String, String> {
+    let actor = caller().to_text();
+    ensure_role(&actor, &[Role::Admin], "configure the HighCompression threshold")?;
+    
+    storage::set_high_compression_threshold(ratio)
+        .map_err(|_| "HighCompression threshold must be finite and greater than 1.0".to_string())?;
+    
+    Ok(format!("HighCompression now requires a {}x ratio", ratio))
+}
+
+#[query]
+#[candid_method(query)]
+fn get_high_compression_threshold() -> f32 {
+    storage::get_high_compression_threshold()
+}
+
+/// Lower (or restore) the chunk size limit; it cannot exceed what fits in
+/// one IC message. Chunks already stored are unaffected.
+#[update]
+#[candid_method(update)]
+fn set_max_chunk_size(bytes: u64) -> Result<String, String> {
+    let actor = caller().to_text();
+    ensure_role(&actor, &[Role::Admin], "configure the chunk size limit")?;
+    
+    storage::set_max_chunk_size(bytes).map_err(|_| format!(
+        "Chunk size limit must be between 1 and {} bytes", storage::MAX_CHUNK_SIZE
+    ))?;
+    
+    Ok(format!("Max chunk size set to {} bytes", bytes))
+}
+
+#[query]
+#[candid_method(query)]
+fn get_max_chunk_size() -> u64 {
+    storage::get_max_chunk_size()
+}
+
+/// How often the heartbeat runs its sweeps; 0 turns them off
+#[update]
+#[candid_method(update)]
+fn set_maintenance_interval(seconds: u64) -> Result<String, String> {
+    let actor = caller().to_text();
+    ensure_role(&actor, &[Role::Admin], "configure the maintenance interval")?;
+    
+    storage::set_maintenance_interval(seconds)
+        .map_err(|e| format!("Persist failed: {:?}", e))?;
+    MAINTENANCE_INTERVAL_NS.with(|interval| interval.set(seconds.saturating_mul(1_000_000_000)));
+    
+    Ok(format!("Maintenance interval set to {} seconds", seconds))
+}
+
+#[update]
+#[candid_method(update)]
+fn set_storage_capacity_bytes(capacity: Option<u64>) -> Result<String, String> {
+    let actor = caller().to_text();
+    ensure_role(&actor, &[Role::Admin], "configure storage capacity")?;
+
+    storage::set_storage_capacity(capacity)
+        .map_err(|e| format!("Persist failed: {:?}", e))?;
+
+    Ok(match capacity {
+        Some(bytes) => format!("Storage capacity set to {} bytes", bytes),
+        None => "Storage capacity limit removed".to_string(),
+    })
+}
+
+#[update]
+#[candid_method(update)]
+fn set_rate_limit(principal: String, limit: u32) -> Result<String, String> {
+    let actor = caller().to_text();
+    ensure_role(&actor, &[Role::Admin], "configure rate limits")?;
+
+    let config = guards::set_rate_limit(principal.clone(), limit);
+    storage::set_rate_limit_config(&config)
+        .map_err(|e| format!("Persist failed: {:?}", e))?;
+
+    Ok(format!("Rate limit for {} set to {}/min", principal, limit))
+}
+
+#[update]
+#[candid_method(update)]
+fn set_default_rate_limit(limit: u32) -> Result<String, String> {
+    let actor = caller().to_text();
+    ensure_role(&actor, &[Role::Admin], "configure rate limits")?;
+    if limit == 0 {
+        return Err("Default rate limit must be greater than 0".to_string());
+    }
+
+    let config = guards::set_default_rate_limit(limit);
+    storage::set_rate_limit_config(&config)
+        .map_err(|e| format!("Persist failed: {:?}", e))?;
+
+    Ok(format!("Default rate limit set to {}/min", limit))
+}
+
+/// (requests in the current one-minute window, limit) for `principal`
+#[query]
+#[candid_method(query)]
+fn get_rate_limit_status(principal: String) -> (u32, u32) {
+    guards::rate_limit_status(&principal)
+}
+
+#[query]
+#[candid_method(query)]
+fn get_storage_usage() -> StorageUsage {
+    storage::get_storage_usage()
+}
+
+/// Keep deprecated manifests for `days` before the heartbeat removes them;
+/// 0 keeps them indefinitely
+#[update]
+#[candid_method(update)]
+fn set_deprecation_retention(days: u64) -> Result<String, String> {
+    let actor = caller().to_text();
+    ensure_role(&actor, &[Role::Admin], "configure deprecation retention")?;
+    
+    storage::set_deprecation_retention(days)
+        .map_err(|e| format!("Persist failed: {:?}", e))?;
+    
+    if days == 0 {
+        Ok("Deprecated models are kept indefinitely".to_string())
+    } else {
+        Ok(format!("Deprecated models are removed after {} days", days))
+    }
+}
+
+#[query]
+#[candid_method(query)]
+fn get_deprecation_retention() -> u64 {
+    storage::get_deprecation_retention()
+}
+
+#[update]
+#[candid_method(update)]
+fn cleanup_deprecated_models() -> Result<String, String> {
+    let actor = caller().to_text();
+    ensure_role(&actor, &[Role::Admin], "cleanup models")?;
     
     let cleaned_count = storage::cleanup_deprecated_models()
         .map_err(|e| format!("Cleanup failed: {:?}", e))?;
+    metrics::record_cycles_sample("cleanup_deprecated_models");
     
     Ok(format!("Cleaned up {} chunks from deprecated models", cleaned_count))
 }
 
 // Health and utility
+#[query]
+#[candid_method(query)]
+fn get_cycles_status() -> metrics::CyclesStatus {
+    metrics::get_cycles_status()
+}
+
 #[query]
 #[candid_method(query)]
 fn health() -> String {
@@ -247,6 +1706,16 @@ fn get_compression_stats() -> String {
     )
 }
 
+/// Interface version and the SHA-256 of the exported candid service, so
+/// clients can detect an interface they were not built against
+#[query]
+#[candid_method(query)]
+fn get_interface_version() -> (String, String) {
+    use sha2::{Digest, Sha256};
+    let hash = hex::encode(Sha256::digest(__export_service().as_bytes()));
+    (INTERFACE_VERSION.to_string(), hash)
+}
+
 // Generate Candid interface
 candid::export_service!();
 
@@ -1,3 +1,4 @@
+use crate::infra::clock::time;
 use candid::{CandidType, Deserialize};
 use serde::Serialize;
 use sha2::Digest;
@@ -32,6 +33,9 @@ pub struct NOVAQModel {
 #[derive(CandidType, Serialize, Deserialize, Clone, Debug)]
 pub struct Verification {
     pub bit_accuracy: f32,
+    pub perplexity_delta: Option<f32>, // Quantized minus baseline perplexity
+    pub max_layer_error: Option<f32>,
+    pub per_subspace_mse: Option<Vec<f32>>, // One entry per NOVAQ subspace
 }
 
 pub type NOVAQVerificationReport = Verification;
@@ -48,7 +52,7 @@ pub struct NOVAQModelCandid {
     pub normalization_metadata: Vec<f32>,     // Flattened NormalizationMetadata
 }
 
-#[derive(CandidType, Serialize, Deserialize, Clone, Debug)]
+#[derive(CandidType, Serialize, Deserialize, Clone, Debug, PartialEq)]
 pub struct NOVAQConfigCandid {
     pub target_bits: f32,
     pub num_subspaces: u32,
@@ -63,6 +67,29 @@ pub struct NOVAQConfigCandid {
     pub seed: u64,
 }
 
+/// fp32 bytes needed to hold tensors of the given shapes
+fn fp32_size_bytes(shapes: impl Iterator<Item = Vec<u64>>) -> u64 {
+    shapes
+        .map(|shape| shape.iter().fold(1u64, |acc, &dim| acc.saturating_mul(dim)).saturating_mul(4))
+        .fold(0u64, |acc, bytes| acc.saturating_add(bytes))
+}
+
+impl NOVAQModel {
+    /// Uncompressed fp32 size of the model, summed over `weight_shapes`
+    pub fn original_size_bytes(&self) -> u64 {
+        fp32_size_bytes(self.weight_shapes.iter()
+            .map(|(_, shape)| shape.iter().map(|&d| d as u64).collect()))
+    }
+}
+
+impl NOVAQModelCandid {
+    /// Uncompressed fp32 size of the model, summed over `weight_shapes`
+    pub fn original_size_bytes(&self) -> u64 {
+        fp32_size_bytes(self.weight_shapes.iter()
+            .map(|(_, shape)| shape.iter().map(|&d| d as u64).collect()))
+    }
+}
+
 impl From<NOVAQModel> for NOVAQModelCandid {
     fn from(model: NOVAQModel) -> Self {
         // Convert weight shapes to Candid format
@@ -137,12 +164,20 @@ pub enum ModelState {
     Deprecated,
 }
 
+// Storage codec for chunk bytes; absent means stored raw
+#[derive(CandidType, Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ChunkCodec {
+    Lz4,
+}
+
 #[derive(CandidType, Serialize, Deserialize, Clone, Debug)]
 pub struct ChunkInfo {
     pub id: String,
     pub offset: u64,
-    pub size: u64,
+    pub size: u64, // Uncompressed; sha256 and offsets refer to these bytes
     pub sha256: String,
+    pub codec: Option<ChunkCodec>, // Set by the canister when stored
+    pub stored_size: Option<u64>, // Bytes held in stable memory
 }
 
 // Enhanced model manifest
@@ -154,18 +189,35 @@ pub struct ModelManifest {
     pub digest: String,
     pub state: ModelState,
     pub uploaded_at: u64,
+    pub uploader: Option<String>, // Submitting principal; None only for legacy manifests
     pub activated_at: Option<u64>,
+    pub deprecated_at: Option<u64>,
+    pub serve_after: Option<u64>, // Chunks are not served before this time
+    pub supersedes: Option<ModelId>, // Previous version, deprecated when this one activates
     // Quantization info
     pub compression_type: CompressionType,
-    pub quantized_model: Option<NOVAQModelCandid>, // Candid-compatible wrapper
+    pub compression: Option<CompressionInfo>, // Set for every compressed type
+    pub novaq_config: Option<NOVAQConfigCandid>, // NOVAQ only; codebooks and indices live in the chunks
+    pub quantized_model: Option<NOVAQModelCandid>, // Legacy embedded copy; stripped on write
 }
 
-#[derive(CandidType, Serialize, Deserialize, Clone, Debug)]
+#[derive(CandidType, Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
 pub enum CompressionType {
     NOVAQ,
+    GPTQ,
+    AWQ,
     Uncompressed,
 }
 
+// Compression figures common to all quantization methods
+#[derive(CandidType, Serialize, Deserialize, Clone, Debug)]
+pub struct CompressionInfo {
+    pub compression_ratio: f32,
+    pub capability_retention: Option<f32>, // Percent of baseline quality kept
+    pub bits_per_weight: Option<f32>,
+    pub original_size_bytes: Option<u64>, // Uncompressed fp32 size
+}
+
 #[derive(CandidType, Serialize, Deserialize, Clone, Debug)]
 pub struct ModelMeta {
     pub family: String,
@@ -199,6 +251,140 @@ pub struct ModelUpload {
     pub chunks: Vec<ChunkData>,
     pub signature: Option<String>,
     pub verification_report: Option<NOVAQVerificationReport>, // Use ohms-adaptq type
+    pub idempotency_key: Option<String>, // Retries with the same key are not reprocessed
+    pub overwrite: Option<bool>, // Admin only; replace an existing model with this id
+    pub compress_chunks: Option<bool>, // Defaults to true; skip for incompressible content
+}
+
+// Principal roles; a principal may hold several
+#[derive(CandidType, Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub enum Role {
+    Admin,
+    Uploader,
+    Auditor,
+    Quantizer, // Canister allowed to submit through submit_quantized_model_ic
+}
+
+// Staged multi-part upload; chunks arrive in separate messages
+#[derive(CandidType, Serialize, Deserialize, Clone, Debug)]
+pub struct UploadSession {
+    pub session_id: String,
+    pub model_id: ModelId,
+    pub manifest: ModelManifest,
+    pub meta: ModelMeta,
+    pub signature: Option<String>,
+    pub owner: String,
+    pub created_at: u64,
+    pub expires_at: u64,
+    pub received_chunks: Vec<String>,
+}
+
+// Outcome of the last independent verification of a model's stored bytes
+#[derive(CandidType, Serialize, Deserialize, Clone, Debug)]
+pub struct VerificationRecord {
+    pub passed: bool,
+    pub verified_at: u64,
+    pub verified_by: String,
+    pub details: String,
+}
+
+// What verify_model checked. The uploader's bit_accuracy cannot be
+// re-derived without the source weights, so it is not reported.
+#[derive(CandidType, Serialize, Deserialize, Clone, Debug)]
+pub struct StructureReport {
+    pub subspaces: u32,
+    pub codebook_entries: u64,
+    pub indices_checked: u64,
+}
+
+// Successful submission recorded under the caller's idempotency key
+#[derive(CandidType, Serialize, Deserialize, Clone, Debug)]
+pub struct IdempotencyRecord {
+    pub model_id: ModelId,
+    pub recorded_at: u64,
+}
+
+// Estimated energy saved by serving a model compressed. Sizes are bytes;
+// energy is kWh, at `energy_per_gb_kwh` per GB (10^9 bytes) saved.
+#[derive(CandidType, Serialize, Deserialize, Clone, Debug)]
+pub struct EnergyReport {
+    pub model_id: ModelId,
+    pub original_size_bytes: u64,
+    pub compressed_size_bytes: u64, // Sum of the manifest's chunk sizes
+    pub saved_bytes: u64,
+    pub energy_per_gb_kwh: f32,
+    pub energy_saved_kwh: f32,
+}
+
+// Upload limits for a principal; None leaves that dimension unlimited
+#[derive(CandidType, Serialize, Deserialize, Clone, Debug, Default)]
+pub struct UploadQuota {
+    pub max_models: Option<u64>,
+    pub max_bytes: Option<u64>,
+}
+
+// A principal's effective quota and what it currently owns
+#[derive(CandidType, Serialize, Deserialize, Clone, Debug)]
+pub struct UploadUsage {
+    pub principal: String,
+    pub quota: UploadQuota,
+    pub models: u64,
+    pub bytes: u64,
+}
+
+// One entry in the reviewers' queue of models awaiting activation
+#[derive(CandidType, Serialize, Deserialize, Clone, Debug)]
+pub struct PendingModelSummary {
+    pub model_id: ModelId,
+    pub version: String,
+    pub uploader: Option<String>, // Actor of the latest Upload audit event
+    pub uploaded_at: u64,
+    pub age_ns: u64,
+    pub compression_type: CompressionType,
+    pub family: Option<String>,
+    pub arch: Option<String>,
+}
+
+// Drift between the in-memory model mirror and stable storage
+#[derive(CandidType, Serialize, Deserialize, Clone, Debug)]
+pub struct RepoStateReport {
+    pub memory_models: u64,
+    pub stable_models: u64,
+    pub missing_in_memory: Vec<ModelId>,
+    pub missing_in_stable: Vec<ModelId>,
+    pub state_mismatches: Vec<(ModelId, ModelState, ModelState)>, // (id, memory, stable)
+    pub in_sync: bool,
+}
+
+// Outcome of rebuilding the in-memory mirror from stable storage
+#[derive(CandidType, Serialize, Deserialize, Clone, Debug)]
+pub struct ResyncReport {
+    pub models_loaded: u64,
+    pub audit_events_loaded: u64,
+    pub reconciled: u64, // Drifted entries the rebuild corrected
+}
+
+// Comparison of two quantizations of the same source model
+#[derive(CandidType, Serialize, Deserialize, Clone, Debug)]
+pub struct ReproducibilityReport {
+    pub model_a: ModelId,
+    pub model_b: ModelId,
+    pub digest_match: bool,
+    pub config_match: bool, // Includes the seed
+    pub max_codebook_delta: Option<f32>, // None when codebook shapes differ
+    pub bit_identical: bool,
+    pub numerically_close: bool,
+    pub reproducible: bool,
+    pub checked_at: u64,
+    pub checked_by: String,
+    pub details: Vec<String>,
+}
+
+// Signature recorded for an upload, verified against the signer's registered key
+#[derive(CandidType, Serialize, Deserialize, Clone, Debug)]
+pub struct SignatureRecord {
+    pub signer: String,
+    pub signature: String,
 }
 
 // Enhanced badge system
@@ -210,7 +396,7 @@ pub struct Badge {
     pub metadata: Option<String>,
 }
 
-#[derive(CandidType, Serialize, Deserialize, Clone, Debug)]
+#[derive(CandidType, Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
 pub enum BadgeType {
     VerifiedQuant,
     Reproducible,
@@ -232,7 +418,7 @@ pub struct AuditEvent {
     pub details: String,
 }
 
-#[derive(CandidType, Serialize, Deserialize, Clone, Debug)]
+#[derive(CandidType, Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
 pub enum AuditEventType {
     Upload,
     Activate,
@@ -241,6 +427,78 @@ pub enum AuditEventType {
     BadgeGrant,
     Quantization,
     Verification,
+    Benchmark,
+    AccessChange,
+    Delete,
+    ChunkReplace,
+    ManifestAccess,
+}
+
+// Audit log filter; every field is optional and set fields must all match
+#[derive(CandidType, Serialize, Deserialize, Clone, Debug, Default)]
+pub struct AuditFilter {
+    pub model_id: Option<ModelId>,
+    pub actor: Option<String>,
+    pub event_type: Option<AuditEventType>,
+    pub from: Option<u64>, // Inclusive
+    pub to: Option<u64>,   // Inclusive
+}
+
+impl AuditFilter {
+    pub fn matches(&self, event: &AuditEvent) -> bool {
+        self.model_id.as_ref().is_none_or(|id| id.0 == event.model_id.0)
+            && self.actor.as_ref().is_none_or(|actor| *actor == event.actor)
+            && self.event_type.as_ref().is_none_or(|t| *t == event.event_type)
+            && self.from.is_none_or(|from| event.timestamp >= from)
+            && self.to.is_none_or(|to| event.timestamp <= to)
+    }
+}
+
+/// One page of the audit log as NDJSON; pass `next_cursor` back to continue
+#[derive(CandidType, Serialize, Deserialize, Clone, Debug)]
+pub struct AuditExport {
+    pub ndjson: String,
+    pub next_cursor: Option<u64>, // Audit index to resume from; None when done
+}
+
+// Benchmark types
+#[derive(CandidType, Serialize, Deserialize, Clone, Debug)]
+pub struct BenchmarkResult {
+    pub metric: String,
+    pub value: f32,
+    pub recorded_at: u64,
+    pub recorded_by: String,
+}
+
+#[derive(CandidType, Serialize, Deserialize, Clone, Debug)]
+pub enum GateComparison {
+    AtMost,
+    AtLeast,
+}
+
+#[derive(CandidType, Serialize, Deserialize, Clone, Debug)]
+pub struct ActivationGate {
+    pub metric: String,
+    pub comparison: GateComparison,
+    pub threshold: f32,
+}
+
+// Integrity sweep types
+#[derive(CandidType, Serialize, Deserialize, Clone, Debug)]
+pub struct IntegrityProblem {
+    pub model_id: String,
+    pub chunk_id: String,
+    pub reason: String,
+}
+
+#[derive(CandidType, Serialize, Deserialize, Clone, Debug)]
+pub struct SweepStatus {
+    pub started_at: u64,
+    pub completed_at: Option<u64>,
+    pub cursor: Option<String>, // Last model id checked
+    pub models_checked: u64,
+    pub chunks_checked: u64,
+    pub problems: Vec<IntegrityProblem>,
 }
 
 // Query types
@@ -251,6 +509,7 @@ pub struct ModelQuery {
     pub min_capability_retention: Option<f32>,
     pub max_size_mb: Option<f32>,
     pub architecture: Option<String>,
+    pub min_perplexity_delta: Option<f32>, // Models without a reported delta never match
 }
 
 #[derive(CandidType, Serialize, Deserialize, Clone, Debug)]
@@ -263,6 +522,50 @@ pub struct ModelStats {
     pub average_capability_retention: f32,
 }
 
+#[derive(CandidType, Serialize, Deserialize, Clone, Debug)]
+pub struct CompressionTypeStats {
+    pub compression_type: CompressionType,
+    pub stats: ModelStats,
+}
+
+// Returned to a quantizer canister so it can record provenance
+#[derive(CandidType, Serialize, Deserialize, Clone, Debug)]
+pub struct QuantizerSubmission {
+    pub model_id: ModelId,
+    pub digest: String,
+}
+
+// Everything needed to render a single model, read in one query
+#[derive(CandidType, Serialize, Deserialize, Clone, Debug)]
+pub struct ModelFull {
+    pub manifest: ModelManifest,
+    pub meta: ModelMeta,
+    pub badges: Vec<Badge>,
+    pub total_size_bytes: u64,
+    pub compression_ratio: Option<f32>,
+}
+
+// Stable-memory footprint, overall and per model
+#[derive(CandidType, Serialize, Deserialize, Clone, Debug)]
+pub struct StorageUsage {
+    pub total_bytes: u64,
+    pub chunk_bytes: u64, // Shared chunks counted once
+    pub manifest_bytes: u64,
+    pub metadata_bytes: u64,
+    pub total_chunks: u64,
+    pub largest_model: Option<String>,
+    pub models: Vec<ModelStorageUsage>,
+    pub stored_chunk_bytes: u64, // Running total checked against capacity
+    pub capacity_bytes: Option<u64>,
+}
+
+#[derive(CandidType, Serialize, Deserialize, Clone, Debug)]
+pub struct ModelStorageUsage {
+    pub model_id: String,
+    pub bytes: u64,
+    pub chunks: u64,
+}
+
 // Error types
 #[derive(CandidType, Serialize, Deserialize, Clone, Debug)]
 pub enum ModelError {
@@ -273,6 +576,11 @@ pub enum ModelError {
     StorageFull,
     UnauthorizedAccess,
     InvalidFormat,
+    InvalidSignature,
+    RateLimited,
+    AlreadyExists,
+    Maintenance,
+    QuotaExceeded,
 }
 
 // Result type
@@ -282,23 +590,84 @@ pub type ModelResult<T> = Result<T, ModelError>;
 impl ModelManifest {
     /// Check if model is quantized
     pub fn is_quantized(&self) -> bool {
-        matches!(self.compression_type, CompressionType::NOVAQ)
+        !matches!(self.compression_type, CompressionType::Uncompressed)
+    }
+
+    /// Compression figures, falling back to the embedded NOVAQ model for
+    /// manifests stored before `compression` existed
+    pub fn compression_info(&self) -> Option<CompressionInfo> {
+        self.compression.clone().or_else(|| {
+            self.quantized_model.as_ref().map(|model| CompressionInfo {
+                compression_ratio: model.compression_ratio,
+                capability_retention: Some(model.bit_accuracy),
+                bits_per_weight: Some(model.config.target_bits),
+                original_size_bytes: Some(model.original_size_bytes()).filter(|&b| b > 0),
+            })
+        })
+    }
+    
+    /// Replace a legacy embedded NOVAQ model with its lightweight fields.
+    /// Returns whether anything was stripped.
+    pub fn strip_embedded_model(&mut self) -> bool {
+        self.compression = self.compression_info();
+        let Some(model) = self.quantized_model.take() else {
+            return false;
+        };
+        if self.novaq_config.is_none() {
+            self.novaq_config = Some(model.config);
+        }
+        true
+    }
+
+    /// Check if chunks may be served at the given time
+    pub fn is_servable_at(&self, now: u64) -> bool {
+        match self.serve_after {
+            Some(serve_after) => now >= serve_after,
+            None => true,
+        }
     }
     
     /// Get compression ratio if available
     pub fn get_compression_ratio(&self) -> Option<f32> {
-        self.quantized_model.as_ref()
-            .map(|model| model.compression_ratio)
+        self.compression_info()
+            .map(|info| info.compression_ratio)
+    }
+
+    /// Get capability retention percentage if available
+    pub fn get_capability_retention(&self) -> Option<f32> {
+        self.compression_info()
+            .and_then(|info| info.capability_retention)
     }
     
-    /// Get compressed size in MB (estimated from compression ratio)
+    /// Uncompressed size in MB, from the recorded byte count when known
+    pub fn original_size_mb(&self) -> f32 {
+        self.compression_info()
+            .and_then(|info| info.original_size_bytes)
+            .map(|bytes| bytes as f32 / 1_000_000.0)
+            .unwrap_or(8000.0) // 8GB typical for large models
+    }
+
+    /// Sum of the chunk sizes, i.e. the bytes actually served
+    pub fn total_chunk_bytes(&self) -> u64 {
+        self.chunks.iter().map(|chunk| chunk.size).sum()
+    }
+
+    /// Get compressed size in MB. `compression_ratio` is a multiplier:
+    /// 4.0 means the model is a quarter of its original size.
     pub fn get_size_mb(&self) -> Option<f32> {
-        // Estimate size based on compression ratio and typical model sizes
-        self.quantized_model.as_ref()
-            .map(|model| {
-                let estimated_original_size = 8000.0; // 8GB typical for large models
-                estimated_original_size * (1.0 - model.compression_ratio / 100.0)
-            })
+        self.get_compression_ratio()
+            .filter(|ratio| *ratio > 0.0)
+            .map(|compression_ratio| self.original_size_mb() / compression_ratio)
+    }
+}
+
+impl ActivationGate {
+    /// Check whether a recorded benchmark value satisfies this gate
+    pub fn is_satisfied_by(&self, value: f32) -> bool {
+        match self.comparison {
+            GateComparison::AtMost => value <= self.threshold,
+            GateComparison::AtLeast => value >= self.threshold,
+        }
     }
 }
 
@@ -311,43 +680,54 @@ impl ModelUpload {
         verification: NOVAQVerificationReport,
     ) -> Self {
         let model_id = ModelId(model_id);
-        let timestamp = ic_cdk::api::time();
+        let timestamp = time();
 
         // Create compressed model data from NOVAQ model
         let candid_model = NOVAQModelCandid::from(quantized_model.clone());
         let bytes = bincode::serialize(&candid_model).unwrap_or_default();
-        let max_chunk: usize = 2 * 1024 * 1024;
+        let max_chunk = crate::services::storage::get_max_chunk_size() as usize;
         let mut chunks: Vec<ChunkData> = Vec::new();
         let mut infos: Vec<ChunkInfo> = Vec::new();
         let mut offset: u64 = 0;
-        let mut hasher = sha2::Sha256::new();
         for (idx, part) in bytes.chunks(max_chunk).enumerate() {
             let chunk_id = format!("novaq-{:06}", idx);
             let sha = sha2::Sha256::digest(part);
-            hasher.update(sha);
             chunks.push(ChunkData { chunk_id: chunk_id.clone(), data: part.to_vec() });
             infos.push(ChunkInfo {
                 id: chunk_id,
                 offset,
                 size: part.len() as u64,
                 sha256: hex::encode(sha),
+                codec: None,
+                stored_size: None,
             });
             offset += part.len() as u64;
         }
-        let digest = hex::encode(hasher.finalize());
-
-        let manifest = ModelManifest {
+        let mut manifest = ModelManifest {
             model_id: model_id.clone(),
             version: "2.0.0".to_string(),
             chunks: infos,
-            digest,
+            digest: String::new(),
             state: ModelState::Pending,
             uploaded_at: timestamp,
+            uploader: None,
             activated_at: None,
+            deprecated_at: None,
+            serve_after: None,
+            supersedes: None,
             compression_type: CompressionType::NOVAQ,
-            // Keep metadata but do not rely on embedded bytes for serving
-            quantized_model: Some(NOVAQModelCandid::from(quantized_model.clone())),
+            compression: Some(CompressionInfo {
+                compression_ratio: quantized_model.compression_ratio,
+                capability_retention: Some(quantized_model.bit_accuracy),
+                bits_per_weight: Some(quantized_model.config.target_bits),
+                original_size_bytes: Some(quantized_model.original_size_bytes()).filter(|&b| b > 0),
+            }),
+            // Codebooks and indices are served from the chunks only
+            novaq_config: Some(candid_model.config.clone()),
+            quantized_model: None,
         };
+        // Use the same canonical digest that submit_model recomputes
+        manifest.digest = crate::services::validation::calculate_manifest_digest(&manifest);
 
         let meta = ModelMeta {
             family: "novaq".to_string(),
@@ -371,6 +751,76 @@ impl ModelUpload {
             chunks,
             signature: None,
             verification_report: Some(verification),
+            idempotency_key: None,
+            overwrite: None,
+            // Quantized weights barely compress
+            compress_chunks: Some(false),
         }
     }
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn event(model: &str, actor: &str, event_type: AuditEventType, timestamp: u64) -> AuditEvent {
+        AuditEvent {
+            event_type,
+            model_id: ModelId(model.to_string()),
+            actor: actor.to_string(),
+            timestamp,
+            details: String::new(),
+        }
+    }
+
+    #[test]
+    fn empty_audit_filter_matches_everything() {
+        let filter = AuditFilter::default();
+        assert!(filter.matches(&event("m1", "alice", AuditEventType::Upload, 10)));
+        assert!(filter.matches(&event("m2", "bob", AuditEventType::Delete, 0)));
+    }
+
+    #[test]
+    fn audit_filter_checks_model_id() {
+        let filter = AuditFilter { model_id: Some(ModelId("m1".to_string())), ..Default::default() };
+        assert!(filter.matches(&event("m1", "alice", AuditEventType::Upload, 10)));
+        assert!(!filter.matches(&event("m2", "alice", AuditEventType::Upload, 10)));
+    }
+
+    #[test]
+    fn audit_filter_checks_actor() {
+        let filter = AuditFilter { actor: Some("alice".to_string()), ..Default::default() };
+        assert!(filter.matches(&event("m1", "alice", AuditEventType::Upload, 10)));
+        assert!(!filter.matches(&event("m1", "bob", AuditEventType::Upload, 10)));
+    }
+
+    #[test]
+    fn audit_filter_checks_event_type() {
+        let filter = AuditFilter { event_type: Some(AuditEventType::Activate), ..Default::default() };
+        assert!(filter.matches(&event("m1", "alice", AuditEventType::Activate, 10)));
+        assert!(!filter.matches(&event("m1", "alice", AuditEventType::Deprecate, 10)));
+    }
+
+    #[test]
+    fn audit_filter_time_bounds_are_inclusive() {
+        let filter = AuditFilter { from: Some(10), to: Some(20), ..Default::default() };
+        assert!(!filter.matches(&event("m1", "alice", AuditEventType::Upload, 9)));
+        assert!(filter.matches(&event("m1", "alice", AuditEventType::Upload, 10)));
+        assert!(filter.matches(&event("m1", "alice", AuditEventType::Upload, 20)));
+        assert!(!filter.matches(&event("m1", "alice", AuditEventType::Upload, 21)));
+    }
+
+    #[test]
+    fn audit_filter_requires_every_set_field() {
+        let filter = AuditFilter {
+            model_id: Some(ModelId("m1".to_string())),
+            actor: Some("alice".to_string()),
+            event_type: Some(AuditEventType::Upload),
+            from: Some(10),
+            to: Some(20),
+        };
+        assert!(filter.matches(&event("m1", "alice", AuditEventType::Upload, 15)));
+        assert!(!filter.matches(&event("m1", "bob", AuditEventType::Upload, 15)));
+        assert!(!filter.matches(&event("m1", "alice", AuditEventType::Upload, 25)));
+    }
+}
@@ -0,0 +1,24 @@
+#[cfg(not(test))]
+pub use ic_cdk::api::time;
+
+#[cfg(test)]
+pub use self::test_clock::{advance, time};
+
+// The IC clock is unavailable off-chain, so tests read a per-thread clock
+// that only moves when a test advances it
+#[cfg(test)]
+mod test_clock {
+    use std::cell::Cell;
+
+    thread_local! {
+        static NOW: Cell<u64> = const { Cell::new(1_700_000_000_000_000_000) };
+    }
+
+    pub fn time() -> u64 {
+        NOW.with(|now| now.get())
+    }
+
+    pub fn advance(ns: u64) {
+        NOW.with(|now| now.set(now.get() + ns));
+    }
+}
@@ -1,22 +1,63 @@
-use ic_cdk::api::caller;
-use std::collections::HashSet;
+use candid::{CandidType, Deserialize};
+use ic_cdk::api::{caller, time};
+use serde::Serialize;
+use std::collections::HashMap;
+
+const RATE_WINDOW_NS: u64 = 60 * 1_000_000_000; // 1 minute in nanoseconds
+const DEFAULT_LIMIT_PER_MINUTE: u32 = 60;
+
+/// Configured limits; request counts are transient and not persisted
+#[derive(CandidType, Serialize, Deserialize, Clone, Debug)]
+pub struct RateLimitConfig {
+    pub default_limit: u32,
+    pub limits: Vec<(String, u32)>,
+}
 
 pub struct RateLimiter {
     requests_per_minute: HashMap<String, u32>,
+    window_started_at: HashMap<String, u64>, // principal -> window start (ns)
     limits: HashMap<String, u32>, // principal -> limit
+    default_limit: u32,
 }
 
-impl RateLimiter {
-    pub fn new() -> Self {
+impl Default for RateLimiter {
+    fn default() -> Self {
         Self {
             requests_per_minute: HashMap::new(),
+            window_started_at: HashMap::new(),
             limits: HashMap::new(),
+            default_limit: DEFAULT_LIMIT_PER_MINUTE,
         }
     }
+}
+
+impl RateLimiter {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    fn limit_for(&self, principal: &str) -> u32 {
+        self.limits.get(principal).copied().unwrap_or(self.default_limit)
+    }
 
     pub fn check_rate_limit(&mut self, principal: &str) -> Result<(), String> {
-        let limit = self.limits.get(principal).unwrap_or(&60); // Default 60/min
-        let current = self.requests_per_minute.get(principal).unwrap_or(&0);
+        self.check_rate_limit_at(principal, time())
+    }
+
+    /// Same as `check_rate_limit` with an explicit clock, so the window reset is testable
+    pub fn check_rate_limit_at(&mut self, principal: &str, now: u64) -> Result<(), String> {
+        // Start a fresh window once the previous one has elapsed
+        let window_expired = match self.window_started_at.get(principal) {
+            Some(&started_at) => now.saturating_sub(started_at) >= RATE_WINDOW_NS,
+            None => true,
+        };
+        if window_expired {
+            self.window_started_at.insert(principal.to_string(), now);
+            self.requests_per_minute.insert(principal.to_string(), 0);
+        }
+
+        let limit = self.limit_for(principal);
+        let current = self.requests_per_minute.get(principal).copied().unwrap_or(0);
         
         if current >= limit {
             return Err("Rate limit exceeded".to_string());
@@ -29,9 +70,43 @@ impl RateLimiter {
     pub fn set_limit(&mut self, principal: String, limit: u32) {
         self.limits.insert(principal, limit);
     }
-}
 
-use std::collections::HashMap;
+    pub fn set_default_limit(&mut self, limit: u32) {
+        self.default_limit = limit;
+    }
+
+    /// Requests counted in the principal's current window, and its limit
+    pub fn status_at(&self, principal: &str, now: u64) -> (u32, u32) {
+        let in_window = self.window_started_at.get(principal)
+            .is_some_and(|&started_at| now.saturating_sub(started_at) < RATE_WINDOW_NS);
+        let current = if in_window {
+            self.requests_per_minute.get(principal).copied().unwrap_or(0)
+        } else {
+            0
+        };
+        (current, self.limit_for(principal))
+    }
+
+    /// Forget principals whose window has elapsed; their next request
+    /// starts a fresh window either way
+    pub fn prune_expired_windows(&mut self, now: u64) {
+        self.window_started_at.retain(|_, started_at| now.saturating_sub(*started_at) < RATE_WINDOW_NS);
+        self.requests_per_minute.retain(|principal, _| self.window_started_at.contains_key(principal));
+    }
+
+    pub fn config(&self) -> RateLimitConfig {
+        let mut limits: Vec<(String, u32)> = self.limits.iter()
+            .map(|(principal, limit)| (principal.clone(), *limit))
+            .collect();
+        limits.sort();
+        RateLimitConfig { default_limit: self.default_limit, limits }
+    }
+
+    pub fn restore(&mut self, config: RateLimitConfig) {
+        self.default_limit = config.default_limit;
+        self.limits = config.limits.into_iter().collect();
+    }
+}
 
 thread_local! {
     static RATE_LIMITER: std::cell::RefCell<RateLimiter> = std::cell::RefCell::new(RateLimiter::new());
@@ -44,6 +119,34 @@ pub fn check_rate_limit() -> Result<(), String> {
     })
 }
 
+pub fn set_rate_limit(principal: String, limit: u32) -> RateLimitConfig {
+    RATE_LIMITER.with(|limiter| {
+        let mut limiter = limiter.borrow_mut();
+        limiter.set_limit(principal, limit);
+        limiter.config()
+    })
+}
+
+pub fn set_default_rate_limit(limit: u32) -> RateLimitConfig {
+    RATE_LIMITER.with(|limiter| {
+        let mut limiter = limiter.borrow_mut();
+        limiter.set_default_limit(limit);
+        limiter.config()
+    })
+}
+
+pub fn rate_limit_status(principal: &str) -> (u32, u32) {
+    RATE_LIMITER.with(|limiter| limiter.borrow().status_at(principal, time()))
+}
+
+pub fn prune_rate_limit_windows(now: u64) {
+    RATE_LIMITER.with(|limiter| limiter.borrow_mut().prune_expired_windows(now));
+}
+
+pub fn restore_rate_limits(config: RateLimitConfig) {
+    RATE_LIMITER.with(|limiter| limiter.borrow_mut().restore(config));
+}
+
 pub fn is_authorized_caller(authorized_principals: &[String]) -> Result<String, String> {
     let caller_id = caller().to_text();
     
@@ -52,4 +155,61 @@ pub fn is_authorized_caller(authorized_principals: &[String]) -> Result<String,
     } else {
         Err("Caller not authorized".to_string())
     }
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const START: u64 = 1_000 * 1_000_000_000;
+
+    #[test]
+    fn requests_over_the_limit_are_rejected_within_a_window() {
+        let mut limiter = RateLimiter::new();
+        limiter.set_limit("alice".to_string(), 2);
+
+        assert!(limiter.check_rate_limit_at("alice", START).is_ok());
+        assert!(limiter.check_rate_limit_at("alice", START + 1).is_ok());
+        assert!(limiter.check_rate_limit_at("alice", START + RATE_WINDOW_NS - 1).is_err());
+        assert_eq!(limiter.status_at("alice", START + 2), (2, 2));
+    }
+
+    #[test]
+    fn window_resets_once_a_minute_has_elapsed() {
+        let mut limiter = RateLimiter::new();
+        limiter.set_limit("alice".to_string(), 1);
+
+        assert!(limiter.check_rate_limit_at("alice", START).is_ok());
+        assert!(limiter.check_rate_limit_at("alice", START + 1).is_err());
+        assert_eq!(limiter.status_at("alice", START + RATE_WINDOW_NS), (0, 1));
+        assert!(limiter.check_rate_limit_at("alice", START + RATE_WINDOW_NS).is_ok());
+        assert!(limiter.check_rate_limit_at("alice", START + RATE_WINDOW_NS + 1).is_err());
+    }
+
+    #[test]
+    fn windows_are_tracked_per_principal() {
+        let mut limiter = RateLimiter::new();
+        limiter.set_default_limit(1);
+
+        assert!(limiter.check_rate_limit_at("alice", START).is_ok());
+        assert!(limiter.check_rate_limit_at("bob", START + 1).is_ok());
+        assert!(limiter.check_rate_limit_at("alice", START + 2).is_err());
+        assert!(limiter.check_rate_limit_at("bob", START + RATE_WINDOW_NS).is_err());
+        assert!(limiter.check_rate_limit_at("alice", START + RATE_WINDOW_NS).is_ok());
+    }
+
+    #[test]
+    fn pruning_drops_only_elapsed_windows() {
+        let mut limiter = RateLimiter::new();
+        limiter.set_default_limit(1);
+        limiter.check_rate_limit_at("alice", START).unwrap();
+        limiter.check_rate_limit_at("bob", START + RATE_WINDOW_NS / 2).unwrap();
+
+        limiter.prune_expired_windows(START + RATE_WINDOW_NS);
+
+        assert!(!limiter.window_started_at.contains_key("alice"));
+        assert!(!limiter.requests_per_minute.contains_key("alice"));
+        assert_eq!(limiter.status_at("bob", START + RATE_WINDOW_NS), (1, 1));
+        assert!(limiter.check_rate_limit_at("alice", START + RATE_WINDOW_NS).is_ok());
+    }
+}
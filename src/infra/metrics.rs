@@ -1,8 +1,9 @@
+use crate::domain::ModelState;
 use candid::{CandidType, Deserialize};
 use serde::Serialize;
 use std::collections::HashMap;
 
-#[derive(CandidType, Serialize, Deserialize, Clone, Debug)]
+#[derive(CandidType, Serialize, Deserialize, Clone, Debug, Default)]
 pub struct Metrics {
     pub total_models: u64,
     pub active_models: u64,
@@ -12,27 +13,33 @@ pub struct Metrics {
     pub total_chunk_accesses: u64,
     pub upload_requests: u64,
     pub activation_requests: u64,
+    pub deprecation_requests: u64,
     pub errors: HashMap<String, u64>,
 }
 
-impl Default for Metrics {
-    fn default() -> Self {
-        Self {
-            total_models: 0,
-            active_models: 0,
-            pending_models: 0,
-            deprecated_models: 0,
-            total_chunks: 0,
-            total_chunk_accesses: 0,
-            upload_requests: 0,
-            activation_requests: 0,
-            errors: HashMap::new(),
-        }
-    }
+/// Cycles balance observed at the end of a tracked update
+#[derive(CandidType, Serialize, Deserialize, Clone, Debug)]
+pub struct CyclesSample {
+    pub operation: String,
+    pub timestamp: u64,
+    pub balance: u128,
 }
 
+#[derive(CandidType, Serialize, Deserialize, Clone, Debug)]
+pub struct CyclesStatus {
+    pub balance: u128,
+    pub recent: Vec<CyclesSample>, // Oldest first
+    pub burn_per_update: Option<u128>, // Average drop between consecutive samples
+    pub burn_per_hour: Option<u128>,
+}
+
+/// Samples kept for the rolling burn estimate
+const MAX_CYCLES_SAMPLES: usize = 50;
+
 thread_local! {
     static METRICS: std::cell::RefCell<Metrics> = std::cell::RefCell::new(Metrics::default());
+    // Not persisted; the estimate restarts after an upgrade
+    static CYCLES_SAMPLES: std::cell::RefCell<Vec<CyclesSample>> = const { std::cell::RefCell::new(Vec::new()) };
 }
 
 pub fn increment_counter(counter: &str) {
@@ -41,6 +48,7 @@ pub fn increment_counter(counter: &str) {
         match counter {
             "upload_requests" => m.upload_requests += 1,
             "activation_requests" => m.activation_requests += 1,
+            "deprecation_requests" => m.deprecation_requests += 1,
             "chunk_accesses" => m.total_chunk_accesses += 1,
             _ => {}
         }
@@ -65,6 +73,72 @@ pub fn update_model_counts(active: u64, pending: u64, deprecated: u64) {
     });
 }
 
+/// Move one model between the state counters. `from` is None for a new
+/// model and `to` is None for a deleted one.
+pub fn record_state_change(from: Option<&ModelState>, to: Option<&ModelState>) {
+    METRICS.with(|metrics| {
+        let mut m = metrics.borrow_mut();
+        if let Some(state) = from {
+            let counter = state_counter(&mut m, state);
+            *counter = counter.saturating_sub(1);
+        }
+        if let Some(state) = to {
+            *state_counter(&mut m, state) += 1;
+        }
+        m.total_models = m.active_models + m.pending_models + m.deprecated_models;
+    });
+}
+
+fn state_counter<'a>(m: &'a mut Metrics, state: &ModelState) -> &'a mut u64 {
+    match state {
+        ModelState::Pending => &mut m.pending_models,
+        ModelState::Active => &mut m.active_models,
+        ModelState::Deprecated => &mut m.deprecated_models,
+    }
+}
+
 pub fn get_metrics() -> Metrics {
     METRICS.with(|metrics| metrics.borrow().clone())
-}
\ No newline at end of file
+}
+
+pub fn record_cycles_sample(operation: &str) {
+    let sample = CyclesSample {
+        operation: operation.to_string(),
+        timestamp: ic_cdk::api::time(),
+        balance: ic_cdk::api::canister_balance128(),
+    };
+    CYCLES_SAMPLES.with(|samples| {
+        let mut samples = samples.borrow_mut();
+        if samples.len() >= MAX_CYCLES_SAMPLES {
+            samples.remove(0);
+        }
+        samples.push(sample);
+    });
+}
+
+/// Current balance plus a burn estimate from the recorded samples. Top-ups
+/// between samples make the estimate low, never negative.
+pub fn get_cycles_status() -> CyclesStatus {
+    let recent = CYCLES_SAMPLES.with(|samples| samples.borrow().clone());
+    let (burn_per_update, burn_per_hour) = match (recent.first(), recent.last()) {
+        (Some(first), Some(last)) if recent.len() > 1 => {
+            let burned = first.balance.saturating_sub(last.balance);
+            let elapsed_ns = last.timestamp.saturating_sub(first.timestamp) as u128;
+            let per_hour = (elapsed_ns > 0).then(|| burned * 3_600_000_000_000 / elapsed_ns);
+            (Some(burned / (recent.len() as u128 - 1)), per_hour)
+        }
+        _ => (None, None),
+    };
+
+    CyclesStatus {
+        balance: ic_cdk::api::canister_balance128(),
+        recent,
+        burn_per_update,
+        burn_per_hour,
+    }
+}
+
+/// Replace the in-memory counters, e.g. with a snapshot restored after upgrade
+pub fn restore_metrics(snapshot: Metrics) {
+    METRICS.with(|metrics| *metrics.borrow_mut() = snapshot);
+}
@@ -1,3 +1,4 @@
+pub mod clock;
 pub mod guards;
 pub mod metrics;
 
@@ -12,6 +13,11 @@ pub fn is_anonymous() -> bool {
     caller() == Principal::anonymous()
 }
 
+/// Canister ids are opaque principals, whose last byte is 0x01
+pub fn is_canister_principal(principal: &Principal) -> bool {
+    principal.as_slice().last() == Some(&0x01)
+}
+
 pub fn require_authenticated() -> Result<String, String> {
     if is_anonymous() {
         Err("Authentication required".to_string())
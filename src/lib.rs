@@ -3,7 +3,6 @@ pub mod domain;
 pub mod services;
 pub mod infra;
 
-pub use api::*;
 pub use domain::*;
 pub use services::*;
 pub use infra::*;
\ No newline at end of file
@@ -1,4 +1,5 @@
 use crate::domain::*;
+use crate::services::storage;
 use candid::{CandidType, Deserialize};
 use serde::Serialize;
 use std::collections::HashMap;
@@ -31,7 +32,7 @@ pub enum Vote {
     Abstain,
 }
 
-#[derive(CandidType, Serialize, Deserialize, Clone, Debug)]
+#[derive(CandidType, Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
 pub enum ProposalStatus {
     Open,
     Passed,
@@ -39,12 +40,75 @@ pub enum ProposalStatus {
     Executed,
 }
 
+/// State change a passed proposal asks the repository to perform
+#[derive(CandidType, Serialize, Deserialize, Clone, Debug)]
+pub enum ExecutionAction {
+    ActivateModel(ModelId),
+    DeprecateModel(ModelId),
+    GrantBadge(ModelId, BadgeType),
+    RevokeBadge(ModelId, BadgeType),
+}
+
+impl From<&GovernanceProposal> for ExecutionAction {
+    fn from(proposal: &GovernanceProposal) -> Self {
+        let model_id = proposal.model_id.clone();
+        match &proposal.proposal_type {
+            ProposalType::ActivateModel => ExecutionAction::ActivateModel(model_id),
+            ProposalType::DeprecateModel => ExecutionAction::DeprecateModel(model_id),
+            ProposalType::GrantBadge(badge) => ExecutionAction::GrantBadge(model_id, badge.clone()),
+            ProposalType::RevokeBadge(badge) => ExecutionAction::RevokeBadge(model_id, badge.clone()),
+        }
+    }
+}
+
+/// Aggregate tally of a proposal; individual choices are not exposed
+#[derive(CandidType, Serialize, Deserialize, Clone, Debug)]
+pub struct ProposalResults {
+    pub proposal_id: u64,
+    pub status: ProposalStatus,
+    pub yes_count: u32,
+    pub no_count: u32,
+    pub abstain_count: u32,
+    pub yes_weight: u64,
+    pub no_weight: u64,
+    pub abstain_weight: u64,
+    pub total_weight: u64, // Weight of every authorized voter
+    pub participation_percent: f32,
+    pub quorum_threshold: u32,
+    pub quorum_met: bool,
+    pub projected_status: ProposalStatus, // Outcome if tallied now
+    pub voting_deadline: u64,
+}
+
 #[derive(CandidType, Serialize, Deserialize, Clone, Debug)]
 pub struct GovernanceConfig {
     pub voting_period_ns: u64,
     pub quorum_threshold: u32,      // Percentage (0-100)
     pub approval_threshold: u32,    // Percentage (0-100)
     pub authorized_voters: Vec<String>,
+    pub voter_weights: HashMap<String, u32>, // Voters not listed weigh 1
+}
+
+impl GovernanceConfig {
+    /// Reject configs under which no proposal could ever be decided sensibly
+    pub fn validate(&self) -> Result<(), String> {
+        if self.quorum_threshold > 100 {
+            return Err(format!("Quorum threshold must be at most 100%, got {}", self.quorum_threshold));
+        }
+        if self.approval_threshold > 100 {
+            return Err(format!("Approval threshold must be at most 100%, got {}", self.approval_threshold));
+        }
+        if self.voting_period_ns == 0 {
+            return Err("Voting period must be greater than 0".to_string());
+        }
+        if self.authorized_voters.is_empty() {
+            return Err("At least one authorized voter is required".to_string());
+        }
+        if let Some((voter, _)) = self.voter_weights.iter().find(|(_, weight)| **weight == 0) {
+            return Err(format!("Voter {} has weight 0", voter));
+        }
+        Ok(())
+    }
 }
 
 impl Default for GovernanceConfig {
@@ -54,24 +118,32 @@ impl Default for GovernanceConfig {
             quorum_threshold: 33, // 33% quorum
             approval_threshold: 66, // 66% approval
             authorized_voters: Vec::new(),
+            voter_weights: HashMap::new(),
         }
     }
 }
 
+#[derive(CandidType, Serialize, Deserialize, Clone, Debug)]
 pub struct GovernanceEngine {
     proposals: HashMap<u64, GovernanceProposal>,
     next_proposal_id: u64,
     config: GovernanceConfig,
 }
 
-impl GovernanceEngine {
-    pub fn new() -> Self {
+impl Default for GovernanceEngine {
+    fn default() -> Self {
         Self {
             proposals: HashMap::new(),
             next_proposal_id: 1,
             config: GovernanceConfig::default(),
         }
     }
+}
+
+impl GovernanceEngine {
+    pub fn new() -> Self {
+        Self::default()
+    }
 
     pub fn create_proposal(
         &mut self,
@@ -85,6 +157,53 @@ impl GovernanceEngine {
             return Err("Proposer not authorized".to_string());
         }
 
+        self.open_proposal(proposal_type, model_id, proposer, description, current_time)
+    }
+
+    /// Open an ActivateModel proposal on behalf of an activation request,
+    /// or return the one already open for the model. The requester need not
+    /// be a voter; the repository has already checked their role.
+    pub fn request_activation(&mut self, model_id: ModelId, requester: String, current_time: u64) -> Result<u64, String> {
+        let description = format!("Activation of {} requested by {}", model_id.0, requester);
+        self.request_proposal(ProposalType::ActivateModel, model_id, requester, description, current_time)
+    }
+
+    /// Same as `request_activation`, for a DeprecateModel proposal
+    pub fn request_deprecation(&mut self, model_id: ModelId, requester: String, current_time: u64) -> Result<u64, String> {
+        let description = format!("Deprecation of {} requested by {}", model_id.0, requester);
+        self.request_proposal(ProposalType::DeprecateModel, model_id, requester, description, current_time)
+    }
+
+    // Variants are compared without their payload, which is only right for
+    // the payload-free Activate/Deprecate proposals
+    fn request_proposal(
+        &mut self,
+        proposal_type: ProposalType,
+        model_id: ModelId,
+        requester: String,
+        description: String,
+        current_time: u64,
+    ) -> Result<u64, String> {
+        let kind = std::mem::discriminant(&proposal_type);
+        let existing = self.proposals.values()
+            .filter(|p| p.status == ProposalStatus::Open && p.model_id.0 == model_id.0)
+            .find(|p| std::mem::discriminant(&p.proposal_type) == kind)
+            .map(|p| p.id);
+        if let Some(proposal_id) = existing {
+            return Ok(proposal_id);
+        }
+
+        self.open_proposal(proposal_type, model_id, requester, description, current_time)
+    }
+
+    fn open_proposal(
+        &mut self,
+        proposal_type: ProposalType,
+        model_id: ModelId,
+        proposer: String,
+        description: String,
+        current_time: u64,
+    ) -> Result<u64, String> {
         let proposal = GovernanceProposal {
             id: self.next_proposal_id,
             proposal_type,
@@ -98,6 +217,8 @@ impl GovernanceEngine {
         };
 
         let proposal_id = self.next_proposal_id;
+        storage::store_proposal(&proposal).map_err(persist_error)?;
+        storage::set_next_proposal_id(proposal_id + 1).map_err(persist_error)?;
         self.proposals.insert(proposal_id, proposal);
         self.next_proposal_id += 1;
 
@@ -118,60 +239,172 @@ impl GovernanceEngine {
         let proposal = self.proposals.get_mut(&proposal_id)
             .ok_or("Proposal not found")?;
 
+        // An early tally closes voting before the deadline
+        if !matches!(proposal.status, ProposalStatus::Open) {
+            return Err("Proposal is not open for voting".to_string());
+        }
+
         if current_time > proposal.voting_deadline {
             return Err("Voting period has ended".to_string());
         }
 
+        proposal.votes.insert(voter, vote);
+        storage::store_proposal(proposal).map_err(persist_error)
+    }
+
+    /// Withdraw a vote entirely, so it no longer counts toward quorum
+    pub fn retract_vote(&mut self, proposal_id: u64, voter: &str, current_time: u64) -> Result<(), String> {
+        let proposal = self.proposals.get_mut(&proposal_id)
+            .ok_or("Proposal not found")?;
+
         if !matches!(proposal.status, ProposalStatus::Open) {
             return Err("Proposal is not open for voting".to_string());
         }
 
-        proposal.votes.insert(voter, vote);
-        Ok(())
+        if current_time > proposal.voting_deadline {
+            return Err("Voting period has ended".to_string());
+        }
+
+        if proposal.votes.remove(voter).is_none() {
+            return Err("No vote to retract".to_string());
+        }
+        storage::store_proposal(proposal).map_err(persist_error)
     }
 
+    /// Decide an open proposal. Allowed once the deadline has passed, or
+    /// early once every authorized voter has voted. Tallying an already
+    /// decided proposal returns its status unchanged.
     pub fn tally_votes(&mut self, proposal_id: u64, current_time: u64) -> Result<ProposalStatus, String> {
         let proposal = self.proposals.get_mut(&proposal_id)
             .ok_or("Proposal not found")?;
 
-        if current_time <= proposal.voting_deadline {
+        if !matches!(proposal.status, ProposalStatus::Open) {
+            return Ok(proposal.status.clone());
+        }
+
+        let all_voted = !self.config.authorized_voters.is_empty()
+            && self.config.authorized_voters.iter().all(|v| proposal.votes.contains_key(v));
+        if current_time <= proposal.voting_deadline && !all_voted {
             return Err("Voting period not yet ended".to_string());
         }
 
-        let total_voters = self.config.authorized_voters.len() as u32;
-        let total_votes = proposal.votes.len() as u32;
-        let yes_votes = proposal.votes.values().filter(|v| matches!(v, Vote::Yes)).count() as u32;
+        let status = Self::outcome(&self.config, proposal);
+        proposal.status = status.clone();
+        storage::store_proposal(proposal).map_err(persist_error)?;
+        Ok(status)
+    }
+
+    /// Tally every open proposal whose deadline has passed; returns the ids
+    /// decided, oldest first
+    pub fn sweep_expired_proposals(&mut self, current_time: u64) -> Vec<u64> {
+        let mut expired: Vec<u64> = self.proposals.values()
+            .filter(|p| p.status == ProposalStatus::Open && current_time > p.voting_deadline)
+            .map(|p| p.id)
+            .collect();
+        expired.sort_unstable();
+        expired.into_iter()
+            .filter(|id| self.tally_votes(*id, current_time).is_ok())
+            .collect()
+    }
+
+    fn weight_of(config: &GovernanceConfig, voter: &str) -> u64 {
+        config.voter_weights.get(voter).copied().unwrap_or(1) as u64
+    }
+
+    fn quorum_met(config: &GovernanceConfig, proposal: &GovernanceProposal) -> bool {
+        let total_weight: u64 = config.authorized_voters.iter().map(|v| Self::weight_of(config, v)).sum();
+        let total_votes: u64 = proposal.votes.keys().map(|v| Self::weight_of(config, v)).sum();
+        (total_votes * 100) >= (total_weight * config.quorum_threshold as u64)
+    }
 
-        // Check quorum
-        let quorum_met = (total_votes * 100) >= (total_voters * self.config.quorum_threshold);
-        
-        if !quorum_met {
-            proposal.status = ProposalStatus::Rejected;
-            return Ok(ProposalStatus::Rejected);
+    /// Passed or Rejected from the votes cast so far. Quorum and approval
+    /// are measured in voting weight, not heads.
+    fn outcome(config: &GovernanceConfig, proposal: &GovernanceProposal) -> ProposalStatus {
+        if !Self::quorum_met(config, proposal) {
+            return ProposalStatus::Rejected;
         }
 
-        // Check approval threshold
-        let approval_met = (yes_votes * 100) >= (total_votes * self.config.approval_threshold);
-        
-        if approval_met {
-            proposal.status = ProposalStatus::Passed;
-            Ok(ProposalStatus::Passed)
+        let total_votes: u64 = proposal.votes.keys().map(|v| Self::weight_of(config, v)).sum();
+        let yes_votes: u64 = proposal.votes.iter()
+            .filter(|(_, v)| matches!(v, Vote::Yes))
+            .map(|(voter, _)| Self::weight_of(config, voter))
+            .sum();
+        if (yes_votes * 100) >= (total_votes * config.approval_threshold as u64) {
+            ProposalStatus::Passed
         } else {
-            proposal.status = ProposalStatus::Rejected;
-            Ok(ProposalStatus::Rejected)
+            ProposalStatus::Rejected
         }
     }
 
-    pub fn execute_proposal(&mut self, proposal_id: u64) -> Result<(), String> {
-        let proposal = self.proposals.get_mut(&proposal_id)
+    /// Live tally of a proposal, open or decided
+    pub fn get_results(&self, proposal_id: u64) -> Option<ProposalResults> {
+        let proposal = self.proposals.get(&proposal_id)?;
+        let config = &self.config;
+
+        let (mut yes_count, mut no_count, mut abstain_count) = (0u32, 0u32, 0u32);
+        let (mut yes_weight, mut no_weight, mut abstain_weight) = (0u64, 0u64, 0u64);
+        for (voter, vote) in &proposal.votes {
+            let weight = Self::weight_of(config, voter);
+            match vote {
+                Vote::Yes => { yes_count += 1; yes_weight += weight; }
+                Vote::No => { no_count += 1; no_weight += weight; }
+                Vote::Abstain => { abstain_count += 1; abstain_weight += weight; }
+            }
+        }
+
+        let total_weight: u64 = config.authorized_voters.iter().map(|v| Self::weight_of(config, v)).sum();
+        let cast_weight = yes_weight + no_weight + abstain_weight;
+        let participation_percent = if total_weight > 0 {
+            cast_weight as f32 * 100.0 / total_weight as f32
+        } else {
+            0.0
+        };
+        let projected_status = match proposal.status {
+            ProposalStatus::Open => Self::outcome(config, proposal),
+            ref decided => decided.clone(),
+        };
+
+        Some(ProposalResults {
+            proposal_id,
+            status: proposal.status.clone(),
+            yes_count,
+            no_count,
+            abstain_count,
+            yes_weight,
+            no_weight,
+            abstain_weight,
+            total_weight,
+            participation_percent,
+            quorum_threshold: config.quorum_threshold,
+            quorum_met: Self::quorum_met(config, proposal),
+            projected_status,
+            voting_deadline: proposal.voting_deadline,
+        })
+    }
+
+    /// Action a passed proposal would perform, without marking it executed
+    pub fn execution_action(&self, proposal_id: u64) -> Result<ExecutionAction, String> {
+        let proposal = self.proposals.get(&proposal_id)
             .ok_or("Proposal not found")?;
 
         if !matches!(proposal.status, ProposalStatus::Passed) {
             return Err("Proposal must be in Passed state to execute".to_string());
         }
 
-        proposal.status = ProposalStatus::Executed;
-        Ok(())
+        Ok(ExecutionAction::from(proposal))
+    }
+
+    /// Mark a passed proposal executed and return the action the caller
+    /// must apply to the repository. `Passed` is only reachable through
+    /// `tally_votes`, so the deadline (or early-tally) rule already holds.
+    pub fn execute_proposal(&mut self, proposal_id: u64) -> Result<ExecutionAction, String> {
+        let action = self.execution_action(proposal_id)?;
+
+        if let Some(proposal) = self.proposals.get_mut(&proposal_id) {
+            proposal.status = ProposalStatus::Executed;
+            storage::store_proposal(proposal).map_err(persist_error)?;
+        }
+        Ok(action)
     }
 
     pub fn get_proposal(&self, proposal_id: u64) -> Option<&GovernanceProposal> {
@@ -182,9 +415,257 @@ impl GovernanceEngine {
         self.proposals.values().collect()
     }
 
-    pub fn add_authorized_voter(&mut self, voter: String) {
+    /// Proposals matching `filter`, oldest first
+    fn filter_proposals(&self, filter: impl Fn(&GovernanceProposal) -> bool) -> Vec<&GovernanceProposal> {
+        let mut proposals: Vec<&GovernanceProposal> = self.proposals.values().filter(|p| filter(p)).collect();
+        proposals.sort_by_key(|p| p.id);
+        proposals
+    }
+
+    pub fn list_proposals_by_status(&self, status: &ProposalStatus) -> Vec<&GovernanceProposal> {
+        self.filter_proposals(|proposal| proposal.status == *status)
+    }
+
+    pub fn list_proposals_for_model(&self, model_id: &ModelId) -> Vec<&GovernanceProposal> {
+        self.filter_proposals(|proposal| proposal.model_id.0 == model_id.0)
+    }
+
+    /// Add a voter, or update an existing voter's weight. Without an
+    /// explicit weight a new voter weighs 1.
+    pub fn add_authorized_voter(&mut self, voter: String, weight: Option<u32>) -> Result<(), String> {
+        if weight == Some(0) {
+            return Err("Voter weight must be greater than 0".to_string());
+        }
+
+        if let Some(weight) = weight {
+            self.config.voter_weights.insert(voter.clone(), weight);
+        }
         if !self.config.authorized_voters.contains(&voter) {
             self.config.authorized_voters.push(voter);
         }
+        storage::set_governance_config(&self.config).map_err(persist_error)
+    }
+
+    pub fn config(&self) -> &GovernanceConfig {
+        &self.config
+    }
+
+    /// Replace the whole config after validating it. Open proposals keep the
+    /// deadline they were created with.
+    pub fn set_config(&mut self, mut config: GovernanceConfig) -> Result<(), String> {
+        config.validate()?;
+        config.authorized_voters.sort();
+        config.authorized_voters.dedup();
+        storage::set_governance_config(&config).map_err(persist_error)?;
+        self.config = config;
+        Ok(())
+    }
+
+    /// Rebuild the in-memory mirror from stable storage, folding in the
+    /// legacy pre_upgrade snapshot if one is still present
+    pub fn load_from_stable(&mut self) {
+        if let Some(legacy) = storage::take_legacy_governance_state() {
+            for proposal in legacy.proposals.values() {
+                let _ = storage::store_proposal(proposal);
+            }
+            let _ = storage::set_next_proposal_id(legacy.next_proposal_id);
+            let _ = storage::set_governance_config(&legacy.config);
+        }
+
+        self.config = storage::get_governance_config().unwrap_or_default();
+        self.proposals = storage::list_stored_proposals()
+            .into_iter()
+            .map(|proposal| (proposal.id, proposal))
+            .collect();
+        // Never reuse an id, even if the counter entry were lost
+        let after_last = self.proposals.keys().max().map_or(1, |id| id + 1);
+        self.next_proposal_id = storage::get_next_proposal_id().unwrap_or(1).max(after_last);
+    }
+}
+
+fn persist_error(error: ModelError) -> String {
+    format!("Persist failed: {:?}", error)
+}
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const DAY_NS: u64 = 24 * 60 * 60 * 1_000_000_000;
+
+    fn engine(voters: &[(&str, Option<u32>)]) -> GovernanceEngine {
+        let mut engine = GovernanceEngine::new();
+        for (voter, weight) in voters {
+            engine.add_authorized_voter(voter.to_string(), *weight).unwrap();
+        }
+        engine
     }
-}
\ No newline at end of file
+
+    fn propose(engine: &mut GovernanceEngine, model_id: &str, now: u64) -> u64 {
+        engine.create_proposal(
+            ProposalType::ActivateModel,
+            ModelId(model_id.to_string()),
+            "alice".to_string(),
+            String::new(),
+            now,
+        ).unwrap()
+    }
+
+    #[test]
+    fn unanimous_approval_can_be_tallied_early() {
+        let mut engine = engine(&[("alice", None), ("bob", None), ("carol", None)]);
+        let id = propose(&mut engine, "m1", 0);
+
+        engine.cast_vote(id, "alice".to_string(), Vote::Yes, 1).unwrap();
+        engine.cast_vote(id, "bob".to_string(), Vote::Yes, 2).unwrap();
+        assert!(engine.tally_votes(id, 3).is_err());
+
+        engine.cast_vote(id, "carol".to_string(), Vote::Yes, 4).unwrap();
+        assert_eq!(engine.tally_votes(id, 5).unwrap(), ProposalStatus::Passed);
+
+        // Voting closes with the tally even though the deadline is days away
+        let err = engine.cast_vote(id, "carol".to_string(), Vote::No, 6).unwrap_err();
+        assert!(err.contains("not open"));
+        assert!(matches!(engine.execute_proposal(id).unwrap(), ExecutionAction::ActivateModel(_)));
+    }
+
+    #[test]
+    fn tallying_twice_returns_the_decided_status() {
+        let mut engine = engine(&[("alice", None), ("bob", None), ("carol", None)]);
+        let id = propose(&mut engine, "m1", 0);
+        engine.cast_vote(id, "alice".to_string(), Vote::Yes, 1).unwrap();
+
+        let after_deadline = 8 * DAY_NS;
+        assert_eq!(engine.tally_votes(id, after_deadline).unwrap(), ProposalStatus::Passed);
+
+        // A stricter quorum now would reject it, but the decision stands
+        let config = GovernanceConfig { quorum_threshold: 100, ..engine.config().clone() };
+        engine.set_config(config).unwrap();
+        assert_eq!(engine.tally_votes(id, after_deadline + 1).unwrap(), ProposalStatus::Passed);
+
+        engine.execute_proposal(id).unwrap();
+        assert_eq!(engine.tally_votes(id, after_deadline + 2).unwrap(), ProposalStatus::Executed);
+        assert!(engine.execute_proposal(id).is_err());
+    }
+
+    #[test]
+    fn a_high_weight_voter_can_meet_quorum_alone() {
+        let mut engine = engine(&[("alice", Some(10)), ("bob", None), ("carol", None)]);
+        let heavy = propose(&mut engine, "m1", 0);
+        let light = propose(&mut engine, "m2", 0);
+
+        engine.cast_vote(heavy, "alice".to_string(), Vote::Yes, 1).unwrap();
+        engine.cast_vote(light, "bob".to_string(), Vote::Yes, 1).unwrap();
+
+        let results = engine.get_results(heavy).unwrap();
+        assert_eq!(results.total_weight, 12);
+        assert_eq!(results.yes_weight, 10);
+        assert!(results.quorum_met);
+        // Bob is one voter in three, but only 1/12 of the weight
+        assert!(!engine.get_results(light).unwrap().quorum_met);
+
+        let after_deadline = 8 * DAY_NS;
+        assert_eq!(engine.tally_votes(heavy, after_deadline).unwrap(), ProposalStatus::Passed);
+        assert_eq!(engine.tally_votes(light, after_deadline).unwrap(), ProposalStatus::Rejected);
+    }
+
+    #[test]
+    fn proposals_filter_by_status_and_model() {
+        let mut engine = engine(&[("alice", None)]);
+        let passed = propose(&mut engine, "m1", 0);
+        let rejected = propose(&mut engine, "m1", 0);
+        let executed = propose(&mut engine, "m2", 0);
+        engine.cast_vote(passed, "alice".to_string(), Vote::Yes, 1).unwrap();
+        engine.cast_vote(rejected, "alice".to_string(), Vote::No, 1).unwrap();
+        engine.cast_vote(executed, "alice".to_string(), Vote::Yes, 1).unwrap();
+        for id in [passed, rejected, executed] {
+            engine.tally_votes(id, 2).unwrap();
+        }
+        engine.execute_proposal(executed).unwrap();
+        let open_m1 = propose(&mut engine, "m1", 3);
+        let open_m3 = propose(&mut engine, "m3", 3);
+
+        let ids = |proposals: Vec<&GovernanceProposal>| proposals.iter().map(|p| p.id).collect::<Vec<_>>();
+        assert_eq!(ids(engine.list_proposals_by_status(&ProposalStatus::Open)), vec![open_m1, open_m3]);
+        assert_eq!(ids(engine.list_proposals_by_status(&ProposalStatus::Passed)), vec![passed]);
+        assert_eq!(ids(engine.list_proposals_by_status(&ProposalStatus::Rejected)), vec![rejected]);
+        assert_eq!(ids(engine.list_proposals_by_status(&ProposalStatus::Executed)), vec![executed]);
+
+        let m1 = ModelId("m1".to_string());
+        assert_eq!(ids(engine.list_proposals_for_model(&m1)), vec![passed, rejected, open_m1]);
+        assert_eq!(ids(engine.list_proposals_for_model(&ModelId("m2".to_string()))), vec![executed]);
+        assert!(engine.list_proposals_for_model(&ModelId("m4".to_string())).is_empty());
+    }
+
+    #[test]
+    fn sweep_decides_only_expired_proposals() {
+        let mut engine = engine(&[("alice", None), ("bob", None)]);
+        let supported = propose(&mut engine, "m1", 0);
+        let ignored = propose(&mut engine, "m2", 0);
+        let recent = propose(&mut engine, "m3", 5 * DAY_NS);
+        engine.cast_vote(supported, "alice".to_string(), Vote::Yes, 1).unwrap();
+        engine.cast_vote(recent, "alice".to_string(), Vote::Yes, 5 * DAY_NS).unwrap();
+
+        let now = 8 * DAY_NS;
+        assert_eq!(engine.sweep_expired_proposals(now), vec![supported, ignored]);
+        assert_eq!(engine.get_proposal(supported).unwrap().status, ProposalStatus::Passed);
+        assert_eq!(engine.get_proposal(ignored).unwrap().status, ProposalStatus::Rejected);
+        assert_eq!(engine.get_proposal(recent).unwrap().status, ProposalStatus::Open);
+
+        // Nothing left to decide until the recent proposal expires
+        assert!(engine.sweep_expired_proposals(now).is_empty());
+        assert_eq!(engine.sweep_expired_proposals(13 * DAY_NS), vec![recent]);
+        assert_eq!(engine.get_proposal(recent).unwrap().status, ProposalStatus::Passed);
+    }
+
+    #[test]
+    fn config_validation_enforces_its_bounds() {
+        let valid = GovernanceConfig {
+            authorized_voters: vec!["alice".to_string()],
+            ..GovernanceConfig::default()
+        };
+        assert!(valid.validate().is_ok());
+
+        // Thresholds are percentages; 0 and 100 are the inclusive bounds
+        for threshold in [0, 100] {
+            assert!(GovernanceConfig { quorum_threshold: threshold, ..valid.clone() }.validate().is_ok());
+            assert!(GovernanceConfig { approval_threshold: threshold, ..valid.clone() }.validate().is_ok());
+        }
+        assert!(GovernanceConfig { quorum_threshold: 101, ..valid.clone() }.validate().is_err());
+        assert!(GovernanceConfig { approval_threshold: 150, ..valid.clone() }.validate().is_err());
+
+        assert!(GovernanceConfig { voting_period_ns: 0, ..valid.clone() }.validate().is_err());
+        assert!(GovernanceConfig { voting_period_ns: 1, ..valid.clone() }.validate().is_ok());
+        assert!(GovernanceConfig { authorized_voters: Vec::new(), ..valid.clone() }.validate().is_err());
+
+        let zero_weight = GovernanceConfig {
+            voter_weights: HashMap::from([("alice".to_string(), 0)]),
+            ..valid.clone()
+        };
+        assert!(zero_weight.validate().is_err());
+    }
+
+    #[test]
+    fn rejected_config_is_not_applied() {
+        let mut engine = engine(&[("alice", None)]);
+        let config = GovernanceConfig { quorum_threshold: 150, ..engine.config().clone() };
+        assert!(engine.set_config(config).is_err());
+        assert_eq!(engine.config().quorum_threshold, 33);
+        assert_eq!(storage::get_governance_config().unwrap().quorum_threshold, 33);
+    }
+
+    #[test]
+    fn a_retracted_vote_drops_the_proposal_below_quorum() {
+        let mut engine = engine(&[("alice", None), ("bob", None), ("carol", None)]);
+        let id = propose(&mut engine, "m1", 0);
+        engine.cast_vote(id, "alice".to_string(), Vote::Yes, 1).unwrap();
+        assert!(engine.get_results(id).unwrap().quorum_met);
+
+        engine.retract_vote(id, "alice", 2).unwrap();
+        let results = engine.get_results(id).unwrap();
+        assert_eq!(results.yes_count, 0);
+        assert!(!results.quorum_met);
+        assert!(engine.retract_vote(id, "alice", 3).is_err());
+
+        assert_eq!(engine.tally_votes(id, 8 * DAY_NS).unwrap(), ProposalStatus::Rejected);
+    }
+}
@@ -0,0 +1,172 @@
+use crate::domain::*;
+use crate::services::storage;
+use sha2::{Digest, Sha256};
+
+/// Check every chunk referenced by a model's manifest against stored bytes.
+/// Returns the number of chunks checked and any problems found.
+pub fn check_model(model_id: &str) -> (u64, Vec<IntegrityProblem>) {
+    let manifest = match storage::get_manifest(model_id) {
+        Ok(manifest) => manifest,
+        Err(e) => {
+            return (0, vec![IntegrityProblem {
+                model_id: model_id.to_string(),
+                chunk_id: String::new(),
+                reason: format!("Manifest unreadable: {:?}", e),
+            }]);
+        }
+    };
+
+    let mut problems = Vec::new();
+    for chunk in &manifest.chunks {
+        let reason = match storage::get_chunk_for_model(model_id, &chunk.id) {
+            Err(_) => Some("Chunk missing".to_string()),
+            Ok(data) if data.len() as u64 != chunk.size => Some(format!(
+                "Size mismatch: manifest {} != stored {}", chunk.size, data.len()
+            )),
+            Ok(data) => {
+                let calculated_hash = hex::encode(Sha256::digest(&data));
+                if calculated_hash != chunk.sha256 {
+                    Some(format!("Hash mismatch: manifest {} != stored {}", chunk.sha256, calculated_hash))
+                } else {
+                    None
+                }
+            }
+        };
+
+        if let Some(reason) = reason {
+            problems.push(IntegrityProblem {
+                model_id: model_id.to_string(),
+                chunk_id: chunk.id.clone(),
+                reason,
+            });
+        }
+    }
+
+    (manifest.chunks.len() as u64, problems)
+}
+
+/// Begin a new sweep. If one is already in progress it is left untouched,
+/// so repeated calls are harmless.
+pub fn start_sweep(now: u64) -> ModelResult<SweepStatus> {
+    if let Some(status) = storage::get_sweep_status() {
+        if status.completed_at.is_none() {
+            return Ok(status);
+        }
+    }
+
+    let status = SweepStatus {
+        started_at: now,
+        completed_at: None,
+        cursor: None,
+        models_checked: 0,
+        chunks_checked: 0,
+        problems: Vec::new(),
+    };
+    storage::set_sweep_status(&status)?;
+    Ok(status)
+}
+
+/// Check up to `budget` further models and persist progress. Continuing a
+/// completed sweep returns its final status unchanged.
+pub fn continue_sweep(budget: u32, now: u64) -> ModelResult<SweepStatus> {
+    let mut status = storage::get_sweep_status().ok_or(ModelError::NotFound)?;
+    if status.completed_at.is_some() {
+        return Ok(status);
+    }
+
+    let batch = storage::list_models_after(status.cursor.as_deref(), budget as usize);
+    for model_id in &batch {
+        let (chunks_checked, problems) = check_model(model_id);
+        status.models_checked += 1;
+        status.chunks_checked += chunks_checked;
+        status.problems.extend(problems);
+        status.cursor = Some(model_id.clone());
+    }
+
+    if batch.len() < budget as usize {
+        status.completed_at = Some(now);
+    }
+
+    storage::set_sweep_status(&status)?;
+    Ok(status)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Store a one-chunk model whose manifest describes `expected` while
+    /// `stored` is what actually lands in chunk storage
+    fn store_model(model_id: &str, expected: &[u8], stored: Option<&[u8]>) {
+        let manifest = ModelManifest {
+            model_id: ModelId(model_id.to_string()),
+            version: "1.0".to_string(),
+            chunks: vec![ChunkInfo {
+                id: "chunk-0".to_string(),
+                offset: 0,
+                size: expected.len() as u64,
+                sha256: hex::encode(Sha256::digest(expected)),
+                codec: None,
+                stored_size: None,
+            }],
+            digest: String::new(),
+            state: ModelState::Active,
+            uploaded_at: 0,
+            uploader: None,
+            activated_at: None,
+            deprecated_at: None,
+            serve_after: None,
+            supersedes: None,
+            compression_type: CompressionType::Uncompressed,
+            compression: None,
+            novaq_config: None,
+            quantized_model: None,
+        };
+        storage::store_manifest(model_id, &manifest).unwrap();
+        if let Some(bytes) = stored {
+            storage::store_chunk_for_model(model_id, "chunk-0", bytes.to_vec(), None).unwrap();
+        }
+    }
+
+    #[test]
+    fn sweep_completes_across_several_continue_calls_and_reports_corruption() {
+        store_model("a", b"alpha", Some(b"alpha"));
+        store_model("b", b"beta", Some(b"btea"));
+        store_model("c", b"gamma", Some(b"gamma"));
+        store_model("d", b"delta", None);
+        store_model("e", b"epsilon", Some(b"epsilon"));
+
+        start_sweep(1).unwrap();
+        let status = continue_sweep(2, 2).unwrap();
+        assert_eq!(status.models_checked, 2);
+        assert_eq!(status.cursor.as_deref(), Some("b"));
+        assert!(status.completed_at.is_none());
+
+        // Starting again mid-sweep keeps the progress made so far
+        assert_eq!(start_sweep(3).unwrap().models_checked, 2);
+
+        let status = continue_sweep(2, 4).unwrap();
+        assert_eq!(status.models_checked, 4);
+        assert!(status.completed_at.is_none());
+
+        let status = continue_sweep(2, 5).unwrap();
+        assert_eq!(status.models_checked, 5);
+        assert_eq!(status.chunks_checked, 5);
+        assert_eq!(status.completed_at, Some(5));
+
+        let mut reported: Vec<(&str, &str)> = status.problems.iter()
+            .map(|p| (p.model_id.as_str(), p.reason.as_str()))
+            .collect();
+        reported.sort();
+        assert_eq!(reported.len(), 2);
+        assert_eq!(reported[0].0, "b");
+        assert!(reported[0].1.starts_with("Hash mismatch"));
+        assert_eq!(reported[1], ("d", "Chunk missing"));
+
+        // Continuing a finished sweep changes nothing
+        let again = continue_sweep(2, 6).unwrap();
+        assert_eq!(again.models_checked, 5);
+        assert_eq!(again.completed_at, Some(5));
+        assert_eq!(storage::get_sweep_status().unwrap().problems.len(), 2);
+    }
+}
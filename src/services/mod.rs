@@ -1,20 +1,93 @@
 pub mod storage;
 pub mod validation;
 pub mod governance;
+pub mod integrity;
+pub mod uploads;
 
 use crate::domain::*;
+use crate::infra::clock::time;
+use crate::services::governance::ExecutionAction;
 use crate::services::storage as storage_stable;
 use candid::{CandidType, Deserialize};
-use ic_cdk::api::time;
 use serde::Serialize;
+use sha2::{Digest, Sha256};
 use std::collections::HashMap;
 
+/// Repository failure: the `ModelError` kind for metrics and typed clients,
+/// plus the human-readable message returned by the string endpoints
+#[derive(Clone, Debug)]
+pub struct RepositoryError {
+    pub kind: ModelError,
+    pub message: String,
+}
+
+impl RepositoryError {
+    pub fn new(kind: ModelError, message: impl Into<String>) -> Self {
+        Self { kind, message: message.into() }
+    }
+}
+
+impl From<RepositoryError> for String {
+    fn from(error: RepositoryError) -> Self {
+        error.message
+    }
+}
+
+pub type RepositoryResult<T> = Result<T, RepositoryError>;
+
+/// Largest byte range returned by one `get_chunk_range` call, kept under the
+/// query response limit
+pub const MAX_RANGE_BYTES: u64 = 2 * 1024 * 1024;
+
+/// Most chunk bytes returned by one `batch_get_chunks` call. A batch stops
+/// before the chunk that would cross it, but always includes the first chunk.
+pub const MAX_BATCH_BYTES: u64 = 2 * 1024 * 1024;
+
+/// Most NDJSON bytes returned by one `export_audit_ndjson` page
+pub const MAX_EXPORT_BYTES: u64 = 2 * 1024 * 1024;
+
+/// Most ids looked up by one `batch_get_model_meta` call; metas are small,
+/// so this keeps a full page well under the response limit
+pub const MAX_META_BATCH: usize = 100;
+
+/// Longest tag accepted, after normalization
+pub const MAX_TAG_LEN: usize = 64;
+
+/// Most tags one model may carry
+pub const MAX_TAGS_PER_MODEL: usize = 32;
+
+/// How long a completed submission's idempotency key is remembered
+pub const IDEMPOTENCY_TTL_NS: u64 = 24 * 60 * 60 * 1_000_000_000;
+
+/// Most idempotency keys kept at once; the oldest are evicted first
+pub const MAX_IDEMPOTENCY_KEYS: usize = 10_000;
+
+/// Wrap a storage-layer error with context, keeping its kind
+fn storage_error(context: &'static str) -> impl Fn(ModelError) -> RepositoryError {
+    move |e| RepositoryError::new(e.clone(), format!("{}: {:?}", context, e))
+}
+
+fn require_role(actor: &str, role: Role, message: &str) -> RepositoryResult<()> {
+    if !storage_stable::has_role(actor, &role) {
+        return Err(RepositoryError::new(ModelError::UnauthorizedAccess, message));
+    }
+    Ok(())
+}
+
+fn require_any_role(actor: &str, roles: &[Role], message: &str) -> RepositoryResult<()> {
+    if !roles.iter().any(|role| storage_stable::has_role(actor, role)) {
+        return Err(RepositoryError::new(ModelError::UnauthorizedAccess, message));
+    }
+    Ok(())
+}
+
+/// Roles that may activate a model directly or request its activation
+const ACTIVATION_ROLES: [Role; 2] = [Role::Admin, Role::Uploader];
+
 #[derive(CandidType, Serialize, Deserialize, Clone, Debug)]
 pub struct ModelRepository {
     models: HashMap<String, ModelManifest>,
-    chunks: HashMap<String, Vec<u8>>,
     audit_log: Vec<AuditEvent>,
-    pub authorized_uploaders: Vec<String>,
     governance_enabled: bool,
 }
 
@@ -22,9 +95,7 @@ impl Default for ModelRepository {
     fn default() -> Self {
         Self {
             models: HashMap::new(),
-            chunks: HashMap::new(),
             audit_log: Vec::new(),
-            authorized_uploaders: Vec::new(),
             governance_enabled: true,
         }
     }
@@ -35,98 +106,334 @@ impl ModelRepository {
         Self::default()
     }
 
-    pub fn submit_model(&mut self, upload: ModelUpload, actor: String) -> Result<(), String> {
+    pub fn submit_model(&mut self, upload: ModelUpload, actor: String) -> RepositoryResult<()> {
+        // Chunks, the manifest and the overwrite check must all key on one id
+        if upload.model_id.0 != upload.manifest.model_id.0 {
+            return Err(RepositoryError::new(
+                ModelError::InvalidFormat,
+                "Manifest model_id does not match upload model_id",
+            ));
+        }
+        let model_id = upload.model_id.0.clone();
+
         // Validate uploader authorization
-        if !self.authorized_uploaders.contains(&actor) {
-            return Err("Unauthorized uploader".to_string());
+        require_role(&actor, Role::Uploader, "Unauthorized uploader")?;
+
+        // A retried submission that already succeeded is not reprocessed
+        let idempotency_key = upload.idempotency_key.as_ref()
+            .map(|token| format!("{}:{}", actor, token));
+        if let Some(key) = &idempotency_key {
+            if let Some(record) = storage_stable::get_idempotency_record(key) {
+                if record.recorded_at.saturating_add(IDEMPOTENCY_TTL_NS) > time() {
+                    if record.model_id.0 != upload.model_id.0 {
+                        return Err(RepositoryError::new(ModelError::InvalidFormat, format!(
+                            "Idempotency key already used for model {}", record.model_id.0
+                        )));
+                    }
+                    return Ok(());
+                }
+            }
+        }
+
+        // Replacing an existing model must be asked for, and only admins may
+        let previous = storage_stable::get_manifest(&model_id).ok();
+        if previous.is_some() {
+            if upload.overwrite != Some(true) {
+                return Err(RepositoryError::new(ModelError::AlreadyExists, format!(
+                    "Model {} already exists", model_id
+                )));
+            }
+            require_role(&actor, Role::Admin, "Only admins may overwrite a model")?;
         }
 
         // Validate manifest integrity
-        self.validate_manifest(&upload.manifest)?;
+        self.validate_manifest(&upload.manifest)
+            .map_err(|e| RepositoryError::new(ModelError::InvalidFormat, e))?;
+
+        // Overwrites are admin-only and do not add a model, so only new
+        // models count against the uploader's quota
+        if previous.is_none() {
+            self.check_upload_quota(&actor, upload.manifest.total_chunk_bytes())?;
+        }
+
+        // A superseded parent must exist and cannot be the model itself
+        if let Some(parent) = &upload.manifest.supersedes {
+            if parent.0 == model_id {
+                return Err(RepositoryError::new(ModelError::InvalidFormat, "Model cannot supersede itself"));
+            }
+            storage_stable::get_manifest(&parent.0)
+                .map_err(|_| RepositoryError::new(ModelError::NotFound, format!("Superseded model {} not found", parent.0)))?;
+        }
+
+        // Recompute the digest rather than trusting the client's value
+        validation::validate_manifest_digest(&upload.manifest)
+            .map_err(|e| RepositoryError::new(ModelError::VerificationFailed, format!("Verification failed: {}", e)))?;
 
-        // Store chunks
+        // Require an Ed25519 signature over the canonical manifest digest
+        self.verify_upload_signature(&upload, &actor)
+            .map_err(|e| RepositoryError::new(ModelError::InvalidSignature, format!("Invalid signature: {}", e)))?;
+
+        // Verify chunk bytes against the manifest before anything is persisted
+        validation::validate_manifest_hashes(&upload.manifest, &upload.chunks)
+            .map_err(|e| RepositoryError::new(ModelError::VerificationFailed, format!("Verification failed: {}", e)))?;
+
+        // Validate every chunk up front so a bad chunk fails before any write
+        for chunk in &upload.chunks {
+            validation::validate_chunk_integrity(chunk).map_err(|e| {
+                RepositoryError::new(ModelError::InvalidFormat, format!("Chunk {} rejected: {}", chunk.chunk_id, e))
+            })?;
+        }
+
+        // Store chunks, tracking what was written so a failure can be rolled back.
+        // An overwrite stages its chunks so the current ones stay intact until
+        // every new chunk and the manifest have been stored.
+        let codec = if upload.compress_chunks == Some(false) { None } else { Some(ChunkCodec::Lz4) };
+        let chunk_namespace = if previous.is_some() {
+            storage_stable::staging_model_id(&model_id)
+        } else {
+            model_id.clone()
+        };
+        let mut stored_chunks: Vec<&str> = Vec::new();
+        let mut stored_info = HashMap::new();
         for chunk in &upload.chunks {
             // Persist chunk under model namespace in stable memory
-            storage_stable::store_chunk_for_model(&upload.model_id.0, &chunk.chunk_id, chunk.data.clone())
-                .map_err(|e| format!("Chunk store error: {:?}", e))?;
-            // Also keep in-memory index for hot path (optional)
-            self.chunks.insert(chunk.chunk_id.clone(), chunk.data.clone());
+            match storage_stable::store_chunk_for_model(&chunk_namespace, &chunk.chunk_id, chunk.data.clone(), codec) {
+                Ok(info) => {
+                    stored_info.insert(chunk.chunk_id.as_str(), info);
+                }
+                Err(e) => {
+                    Self::rollback_chunks(&chunk_namespace, &stored_chunks);
+                    return Err(storage_error("Chunk store error")(e));
+                }
+            }
+            stored_chunks.push(&chunk.chunk_id);
         }
 
-        // Store manifest as Pending
+        // Store manifest as Pending, recording how each chunk was stored
         let mut manifest = upload.manifest;
+        for info in &mut manifest.chunks {
+            let stored = stored_info.get(info.id.as_str());
+            info.codec = stored.and_then(|(codec, _)| *codec);
+            info.stored_size = stored.map(|(_, size)| *size);
+        }
+        manifest.strip_embedded_model();
         manifest.state = ModelState::Pending;
         manifest.uploaded_at = time();
+        manifest.uploader = Some(actor.clone());
+        manifest.serve_after = None;
         
         // Persist manifest/meta to stable memory
-        storage_stable::store_manifest(&manifest.model_id.0, &manifest)
-            .map_err(|e| format!("Manifest store error: {:?}", e))?;
-        storage_stable::store_model_meta(&manifest.model_id.0, &upload.meta)
-            .map_err(|e| format!("Meta store error: {:?}", e))?;
+        let persisted = storage_stable::store_manifest(&manifest.model_id.0, &manifest)
+            .map_err(storage_error("Manifest store error"))
+            .and_then(|_| {
+                storage_stable::store_model_meta(&manifest.model_id.0, &upload.meta)
+                    .map_err(storage_error("Meta store error"))
+            });
+        if let Err(e) = persisted {
+            Self::rollback_chunks(&chunk_namespace, &stored_chunks);
+            return Err(e);
+        }
 
+        // Keep the manifest in the in-memory index; chunk bytes stay in stable memory
         self.models.insert(manifest.model_id.0.clone(), manifest.clone());
 
+        // Swap the staged chunks in, drop chunks the replaced manifest had but
+        // this one does not, and clear the verification records, which
+        // described the old bytes, along with the badges they earned
+        if let Some(previous) = &previous {
+            for chunk_id in &stored_chunks {
+                storage_stable::promote_staged_chunk(&model_id, chunk_id);
+            }
+            for chunk in &previous.chunks {
+                if !manifest.chunks.iter().any(|c| c.id == chunk.id) {
+                    storage_stable::remove_chunk_for_model(&model_id, &chunk.id);
+                }
+            }
+            storage_stable::remove_verification(&manifest.model_id.0);
+            storage_stable::remove_reproducibility(&manifest.model_id.0);
+            storage_stable::remove_quality_report(&manifest.model_id.0);
+
+            let mut badges = storage_stable::get_badges(&manifest.model_id.0);
+            let held = badges.len();
+            badges.retain(|b| !matches!(b.badge_type, BadgeType::VerifiedQuant | BadgeType::Reproducible));
+            if badges.len() != held {
+                storage_stable::set_badges(&manifest.model_id.0, &badges)
+                    .map_err(storage_error("Badge store error"))?;
+            }
+        }
+
+        if let Some(report) = &upload.verification_report {
+            storage_stable::store_quality_report(&manifest.model_id.0, report)
+                .map_err(storage_error("Quality report store error"))?;
+        }
+
+        // Keep the upload signature so it can be re-verified later
+        if let Some(signature) = upload.signature {
+            let record = SignatureRecord { signer: actor.clone(), signature };
+            storage_stable::store_signature(&manifest.model_id.0, &record)
+                .map_err(storage_error("Signature store error"))?;
+        }
+
         // Log audit event
         let event = AuditEvent {
             event_type: AuditEventType::Upload,
             model_id: manifest.model_id,
             actor,
             timestamp: time(),
-            details: format!("Model uploaded with {} chunks", upload.chunks.len()),
+            details: format!(
+                "Model {} with {} chunks",
+                if previous.is_some() { "overwritten" } else { "uploaded" },
+                upload.chunks.len()
+            ),
         };
         storage_stable::append_audit_event(&event).ok();
         self.audit_log.push(event);
 
+        if let Some(key) = idempotency_key {
+            let now = time();
+            let record = IdempotencyRecord { model_id: upload.model_id, recorded_at: now };
+            storage_stable::record_idempotency_key(
+                &key,
+                &record,
+                now.saturating_sub(IDEMPOTENCY_TTL_NS),
+                MAX_IDEMPOTENCY_KEYS,
+            ).map_err(storage_error("Idempotency key store error"))?;
+        }
+
         Ok(())
     }
 
-    pub fn activate_model(&mut self, model_id: &ModelId, actor: String) -> Result<(), String> {
+    pub fn activate_model(
+        &mut self,
+        model_id: &ModelId,
+        serve_after: Option<u64>,
+        actor: String,
+    ) -> RepositoryResult<()> {
+        // With governance on, activation only happens by executing an
+        // ActivateModel proposal
         if self.governance_enabled {
-            // In real implementation, check governance vote
-            // For now, just check if actor is authorized
-            if !self.authorized_uploaders.contains(&actor) {
-                return Err("Governance approval required".to_string());
+            return Err(RepositoryError::new(ModelError::UnauthorizedAccess, "Governance approval required"));
+        }
+        require_any_role(&actor, &ACTIVATION_ROLES, "Not authorized to activate models")?;
+
+        self.transition_to_active(model_id, serve_after, actor)
+    }
+
+    pub fn governance_enabled(&self) -> bool {
+        self.governance_enabled
+    }
+
+    pub fn set_governance_enabled(&mut self, enabled: bool, actor: String) -> RepositoryResult<()> {
+        require_role(&actor, Role::Admin, "Not authorized to toggle governance")?;
+        storage_stable::set_governance_enabled(enabled)
+            .map_err(storage_error("Persist failed"))?;
+        self.governance_enabled = enabled;
+        Ok(())
+    }
+
+    /// Check that `actor` may ask for `model_id` to be activated and that it
+    /// is still Pending, before a proposal is opened for it
+    pub fn check_activation_request(&self, model_id: &ModelId, actor: &str) -> RepositoryResult<()> {
+        require_any_role(actor, &ACTIVATION_ROLES, "Not authorized to request activation")?;
+        let model = storage_stable::get_manifest(&model_id.0)
+            .map_err(|_| RepositoryError::new(ModelError::NotFound, "Model not found"))?;
+        if !matches!(model.state, ModelState::Pending) {
+            return Err(RepositoryError::new(ModelError::InvalidState, "Model must be in Pending state"));
+        }
+        Ok(())
+    }
+
+    /// Apply an action produced by an executed governance proposal
+    pub fn apply_governance_action(&mut self, action: ExecutionAction, actor: String) -> RepositoryResult<()> {
+        match action {
+            // The passed proposal is the approval, so skip the uploader check
+            ExecutionAction::ActivateModel(model_id) => self.transition_to_active(&model_id, None, actor),
+            ExecutionAction::DeprecateModel(model_id) => self.deprecate_model(&model_id, actor),
+            ExecutionAction::GrantBadge(model_id, badge_type) => {
+                self.attach_badge(&model_id, badge_type, None, actor)
+            }
+            ExecutionAction::RevokeBadge(model_id, badge_type) => {
+                self.detach_badge(&model_id, badge_type, actor)
             }
         }
+    }
 
+    fn transition_to_active(
+        &mut self,
+        model_id: &ModelId,
+        serve_after: Option<u64>,
+        actor: String,
+    ) -> RepositoryResult<()> {
         // Source of truth is stable storage; load, mutate, then persist
         let mut model = storage_stable::get_manifest(&model_id.0)
-            .map_err(|_| "Model not found".to_string())?;
+            .map_err(|_| RepositoryError::new(ModelError::NotFound, "Model not found"))?;
 
         if !matches!(model.state, ModelState::Pending) {
-            return Err("Model must be in Pending state".to_string());
+            return Err(RepositoryError::new(ModelError::InvalidState, "Model must be in Pending state"));
+        }
+
+        self.check_activation_gates(model_id)?;
+
+        // A partially uploaded model must never go live
+        if let Some(missing) = model.chunks.iter()
+            .find(|chunk| !storage_stable::has_chunk_for_model(&model_id.0, &chunk.id))
+        {
+            return Err(RepositoryError::new(
+                ModelError::VerificationFailed,
+                format!("Chunk {} is missing from storage", missing.id),
+            ));
         }
 
         model.state = ModelState::Active;
         model.activated_at = Some(time());
+        model.serve_after = serve_after;
         // Persist updated manifest to stable storage
         storage_stable::store_manifest(&model_id.0, &model)
-            .map_err(|e| format!("Persist failed: {:?}", e))?;
+            .map_err(storage_error("Persist failed"))?;
         // Update in-memory mirror
         self.models.insert(model_id.0.clone(), model.clone());
 
         let event = AuditEvent {
             event_type: AuditEventType::Activate,
             model_id: model_id.clone(),
-            actor,
+            actor: actor.clone(),
             timestamp: time(),
-            details: "Model activated".to_string(),
+            details: match serve_after {
+                Some(ts) => format!("Model activated, servable after {}", ts),
+                None => "Model activated".to_string(),
+            },
         };
         storage_stable::append_audit_event(&event).ok();
         self.audit_log.push(event);
 
+        // Roll the chain forward: the version this one replaces stops serving
+        if let Some(parent) = &model.supersedes {
+            let parent_active = storage_stable::get_manifest(&parent.0)
+                .is_ok_and(|m| matches!(m.state, ModelState::Active));
+            if parent_active {
+                self.deprecate_model(parent, actor)?;
+            }
+        }
+
         Ok(())
     }
 
-    pub fn deprecate_model(&mut self, model_id: &ModelId, actor: String) -> Result<(), String> {
-        let model = self.models.get_mut(&model_id.0)
-            .ok_or("Model not found")?;
+    pub fn deprecate_model(&mut self, model_id: &ModelId, actor: String) -> RepositoryResult<()> {
+        // Source of truth is stable storage; load, mutate, then persist
+        let mut model = storage_stable::get_manifest(&model_id.0)
+            .map_err(|_| RepositoryError::new(ModelError::NotFound, "Model not found"))?;
 
         if !matches!(model.state, ModelState::Active) {
-            return Err("Model must be Active to deprecate".to_string());
+            return Err(RepositoryError::new(ModelError::InvalidState, "Model must be Active to deprecate"));
         }
 
         model.state = ModelState::Deprecated;
+        model.deprecated_at = Some(time());
+        // Persist updated manifest to stable storage
+        storage_stable::store_manifest(&model_id.0, &model)
+            .map_err(storage_error("Persist failed"))?;
+        // Update in-memory mirror
+        self.models.insert(model_id.0.clone(), model);
 
         let event = AuditEvent {
             event_type: AuditEventType::Deprecate,
@@ -141,78 +448,1798 @@ impl ModelRepository {
         Ok(())
     }
 
-    pub fn get_manifest(&self, model_id: &ModelId) -> Option<&ModelManifest> {
-        self.models.get(&model_id.0)
+    /// Deprecate each of `model_ids` that is Active, skipping the rest.
+    /// Stops at the first failure; models already handled stay deprecated.
+    pub fn deprecate_models(&mut self, model_ids: &[ModelId], actor: String) -> RepositoryResult<Vec<ModelId>> {
+        let mut deprecated = Vec::new();
+        for model_id in model_ids {
+            let active = storage_stable::get_manifest(&model_id.0)
+                .is_ok_and(|m| matches!(m.state, ModelState::Active));
+            if active {
+                self.deprecate_model(model_id, actor.clone())?;
+                deprecated.push(model_id.clone());
+            }
+        }
+        Ok(deprecated)
     }
 
-    pub fn get_chunk(&mut self, model_id: &ModelId, chunk_id: &str, actor: String) -> Option<Vec<u8>> {
-        // Verify model exists and is active
-        let model = self.models.get(&model_id.0)?;
-        if !matches!(model.state, ModelState::Active) {
-            return None;
+    /// Hard-delete a deprecated model. The `Delete` audit event is written
+    /// before anything is removed so the deletion itself is on record.
+    pub fn delete_model(&mut self, model_id: &ModelId, actor: String) -> RepositoryResult<()> {
+        require_role(&actor, Role::Admin, "Not authorized to delete models")?;
+
+        let manifest = storage_stable::get_manifest(&model_id.0)
+            .map_err(|_| RepositoryError::new(ModelError::NotFound, "Model not found"))?;
+        if !matches!(manifest.state, ModelState::Deprecated) {
+            return Err(RepositoryError::new(ModelError::InvalidState, "Only Deprecated models can be deleted"));
+        }
+
+        let details = format!("Model deleted with {} chunks", manifest.chunks.len());
+        self.remove_model(&manifest, actor, details)
+    }
+
+    /// Remove deprecated models whose retention window has passed, logging a
+    /// `Delete` event for each. Models deprecated before `deprecated_at` was
+    /// recorded start their window now.
+    pub fn purge_expired_deprecated(&mut self, now: u64, retention_days: u64, actor: String) -> RepositoryResult<Vec<ModelId>> {
+        let retention_ns = retention_days.saturating_mul(24 * 60 * 60 * 1_000_000_000);
+        let mut purged = Vec::new();
+        for model_id in storage_stable::list_models() {
+            let Ok(mut manifest) = storage_stable::get_manifest(&model_id) else {
+                continue;
+            };
+            if !matches!(manifest.state, ModelState::Deprecated) {
+                continue;
+            }
+            let Some(deprecated_at) = manifest.deprecated_at else {
+                manifest.deprecated_at = Some(now);
+                storage_stable::store_manifest(&model_id, &manifest)
+                    .map_err(storage_error("Persist failed"))?;
+                self.models.insert(model_id, manifest);
+                continue;
+            };
+            if now.saturating_sub(deprecated_at) < retention_ns {
+                continue;
+            }
+            let details = format!("Deprecated model removed after {}-day retention", retention_days);
+            self.remove_model(&manifest, actor.clone(), details)?;
+            purged.push(manifest.model_id);
         }
+        Ok(purged)
+    }
 
-        // Log access
+    // Shared by delete_model and the retention sweep; no role or state checks
+    fn remove_model(&mut self, manifest: &ModelManifest, actor: String, details: String) -> RepositoryResult<()> {
         let event = AuditEvent {
-            event_type: AuditEventType::ChunkAccess,
+            event_type: AuditEventType::Delete,
+            model_id: manifest.model_id.clone(),
+            actor,
+            timestamp: time(),
+            details,
+        };
+        storage_stable::append_audit_event(&event)
+            .map_err(storage_error("Audit store error"))?;
+        self.audit_log.push(event);
+
+        storage_stable::delete_model_records(manifest)
+            .map_err(storage_error("Delete failed"))?;
+        self.models.remove(&manifest.model_id.0);
+
+        Ok(())
+    }
+
+    /// Rewrite one stored chunk with bytes matching the manifest's hash,
+    /// to repair corruption. Only the chunk's storage fields in the manifest
+    /// may change, so this cannot alter a model's content.
+    pub fn replace_chunk(
+        &mut self,
+        model_id: &ModelId,
+        chunk_id: &str,
+        data: Vec<u8>,
+        actor: String,
+    ) -> RepositoryResult<()> {
+        require_role(&actor, Role::Admin, "Not authorized to replace chunks")?;
+
+        let mut manifest = storage_stable::get_manifest(&model_id.0)
+            .map_err(|_| RepositoryError::new(ModelError::NotFound, "Model not found"))?;
+        let info = manifest.chunks.iter_mut()
+            .find(|c| c.id == chunk_id)
+            .ok_or_else(|| RepositoryError::new(ModelError::NotFound, "Chunk not in manifest"))?;
+
+        let chunk = ChunkData { chunk_id: chunk_id.to_string(), data };
+        validation::validate_chunk_integrity(&chunk)
+            .map_err(|e| RepositoryError::new(ModelError::InvalidFormat, format!("Chunk {} rejected: {}", chunk_id, e)))?;
+        if chunk.data.len() as u64 != info.size {
+            return Err(RepositoryError::new(ModelError::VerificationFailed, format!(
+                "Chunk size mismatch for {}: {} != {}", chunk_id, info.size, chunk.data.len()
+            )));
+        }
+        let hash = hex::encode(Sha256::digest(&chunk.data));
+        if !hash.eq_ignore_ascii_case(&info.sha256) {
+            return Err(RepositoryError::new(ModelError::VerificationFailed, format!(
+                "Hash mismatch for chunk {}: {} != {}", chunk_id, info.sha256, hash
+            )));
+        }
+
+        // Keep the chunk's existing codec choice
+        let (codec, stored_size) = storage_stable::store_chunk_for_model(&model_id.0, chunk_id, chunk.data, info.codec)
+            .map_err(storage_error("Chunk store error"))?;
+        if info.codec != codec || info.stored_size != Some(stored_size) {
+            info.codec = codec;
+            info.stored_size = Some(stored_size);
+            storage_stable::store_manifest(&model_id.0, &manifest)
+                .map_err(storage_error("Manifest store error"))?;
+            self.models.insert(model_id.0.clone(), manifest);
+        }
+
+        let event = AuditEvent {
+            event_type: AuditEventType::ChunkReplace,
             model_id: model_id.clone(),
             actor,
             timestamp: time(),
-            details: format!("Chunk {} accessed", chunk_id),
+            details: format!("Chunk {} replaced", chunk_id),
         };
         storage_stable::append_audit_event(&event).ok();
         self.audit_log.push(event);
 
-        // Try in-memory first, then stable as source of truth
-        self.chunks.get(chunk_id)
-            .cloned()
-            .or_else(|| storage_stable::get_chunk_for_model(&model_id.0, chunk_id).ok())
+        Ok(())
     }
 
-    pub fn list_models(&self, state_filter: Option<ModelState>) -> Vec<&ModelManifest> {
-        self.models
-            .values()
-            .filter(|m| {
-                if let Some(ref filter_state) = state_filter {
-                    std::mem::discriminant(&m.state) == std::mem::discriminant(filter_state)
-                } else {
-                    true
-                }
-            })
-            .collect()
+    /// Store a manifest migrated from another registry, Pending and without
+    /// chunk data; the chunks are then filled in with `replace_chunk`
+    pub fn import_manifest(
+        &mut self,
+        mut manifest: ModelManifest,
+        meta: Option<ModelMeta>,
+        actor: String,
+    ) -> RepositoryResult<ModelId> {
+        require_role(&actor, Role::Admin, "Not authorized to import manifests")?;
+
+        if storage_stable::get_manifest(&manifest.model_id.0).is_ok() {
+            return Err(RepositoryError::new(ModelError::AlreadyExists, format!(
+                "Model {} already exists", manifest.model_id.0
+            )));
+        }
+        self.validate_manifest(&manifest)
+            .map_err(|e| RepositoryError::new(ModelError::InvalidFormat, e))?;
+        validation::validate_manifest_digest(&manifest)
+            .map_err(|e| RepositoryError::new(ModelError::VerificationFailed, format!("Verification failed: {}", e)))?;
+
+        // Nothing is stored yet, so no chunk has a storage encoding
+        for chunk in &mut manifest.chunks {
+            chunk.codec = None;
+            chunk.stored_size = None;
+        }
+        manifest.strip_embedded_model();
+        manifest.state = ModelState::Pending;
+        manifest.uploaded_at = time();
+        // Keep the original uploader when the export carried one
+        if manifest.uploader.is_none() {
+            manifest.uploader = Some(actor.clone());
+        }
+        manifest.activated_at = None;
+        manifest.serve_after = None;
+
+        storage_stable::store_manifest(&manifest.model_id.0, &manifest)
+            .map_err(storage_error("Manifest store error"))?;
+        if let Some(meta) = &meta {
+            storage_stable::store_model_meta(&manifest.model_id.0, meta)
+                .map_err(storage_error("Meta store error"))?;
+        }
+        self.models.insert(manifest.model_id.0.clone(), manifest.clone());
+
+        let event = AuditEvent {
+            event_type: AuditEventType::Upload,
+            model_id: manifest.model_id.clone(),
+            actor,
+            timestamp: time(),
+            details: format!("Manifest imported with {} chunks pending upload", manifest.chunks.len()),
+        };
+        storage_stable::append_audit_event(&event).ok();
+        self.audit_log.push(event);
+
+        Ok(manifest.model_id)
     }
 
-    fn validate_manifest(&self, manifest: &ModelManifest) -> Result<(), String> {
-        if manifest.chunks.is_empty() {
-            return Err("Manifest must contain at least one chunk".to_string());
+    /// Add normalized tags to a model; returns the resulting tag set
+    pub fn add_tags(&mut self, model_id: &ModelId, tags: Vec<String>, actor: String) -> RepositoryResult<Vec<String>> {
+        let mut current = self.taggable_model(model_id, &actor)?;
+        for tag in tags {
+            let tag = storage_stable::normalize_tag(&tag);
+            if tag.is_empty() || tag.len() > MAX_TAG_LEN {
+                return Err(RepositoryError::new(ModelError::InvalidFormat, format!(
+                    "Tags must be 1 to {} characters", MAX_TAG_LEN
+                )));
+            }
+            if !current.contains(&tag) {
+                current.push(tag);
+            }
+        }
+        if current.len() > MAX_TAGS_PER_MODEL {
+            return Err(RepositoryError::new(ModelError::InvalidFormat, format!(
+                "A model may carry at most {} tags", MAX_TAGS_PER_MODEL
+            )));
         }
 
-        for chunk in &manifest.chunks {
-            if chunk.size > 2 * 1024 * 1024 {
-                return Err(format!("Chunk {} exceeds 2MiB limit", chunk.id));
+        storage_stable::set_model_tags(&model_id.0, &current)
+            .map_err(storage_error("Tag store error"))?;
+        Ok(current)
+    }
+
+    /// Remove tags from a model; tags it does not carry are ignored
+    pub fn remove_tags(&mut self, model_id: &ModelId, tags: Vec<String>, actor: String) -> RepositoryResult<Vec<String>> {
+        let mut current = self.taggable_model(model_id, &actor)?;
+        let removed: Vec<String> = tags.iter().map(|t| storage_stable::normalize_tag(t)).collect();
+        current.retain(|tag| !removed.contains(tag));
+
+        storage_stable::set_model_tags(&model_id.0, &current)
+            .map_err(storage_error("Tag store error"))?;
+        Ok(current)
+    }
+
+    fn taggable_model(&self, model_id: &ModelId, actor: &str) -> RepositoryResult<Vec<String>> {
+        if !storage_stable::has_role(actor, &Role::Admin) {
+            require_role(actor, Role::Uploader, "Not authorized to tag models")?;
+        }
+        storage_stable::get_manifest(&model_id.0)
+            .map_err(|_| RepositoryError::new(ModelError::NotFound, "Model not found"))?;
+        Ok(storage_stable::get_model_tags(&model_id.0))
+    }
+
+    pub fn record_benchmark(
+        &mut self,
+        model_id: &ModelId,
+        metric: String,
+        value: f32,
+        actor: String,
+    ) -> RepositoryResult<()> {
+        require_role(&actor, Role::Uploader, "Not authorized to record benchmarks")?;
+
+        if metric.trim().is_empty() {
+            return Err(RepositoryError::new(ModelError::InvalidFormat, "Benchmark metric cannot be empty"));
+        }
+
+        storage_stable::get_manifest(&model_id.0)
+            .map_err(|_| RepositoryError::new(ModelError::NotFound, "Model not found"))?;
+
+        let result = BenchmarkResult {
+            metric: metric.clone(),
+            value,
+            recorded_at: time(),
+            recorded_by: actor.clone(),
+        };
+        storage_stable::append_benchmark(&model_id.0, &result)
+            .map_err(storage_error("Benchmark store error"))?;
+
+        let event = AuditEvent {
+            event_type: AuditEventType::Benchmark,
+            model_id: model_id.clone(),
+            actor,
+            timestamp: time(),
+            details: format!("Benchmark {} recorded: {}", metric, value),
+        };
+        storage_stable::append_audit_event(&event).ok();
+        self.audit_log.push(event);
+
+        Ok(())
+    }
+
+    pub fn grant_badge(
+        &mut self,
+        model_id: &ModelId,
+        badge_type: BadgeType,
+        metadata: Option<String>,
+        actor: String,
+    ) -> RepositoryResult<()> {
+        require_role(&actor, Role::Admin, "Not authorized to grant badges")?;
+
+        self.attach_badge(model_id, badge_type, metadata, actor)
+    }
+
+    pub fn revoke_badge(&mut self, model_id: &ModelId, badge_type: BadgeType, actor: String) -> RepositoryResult<()> {
+        require_role(&actor, Role::Admin, "Not authorized to revoke badges")?;
+
+        self.detach_badge(model_id, badge_type, actor)
+    }
+
+    fn attach_badge(
+        &mut self,
+        model_id: &ModelId,
+        badge_type: BadgeType,
+        metadata: Option<String>,
+        actor: String,
+    ) -> RepositoryResult<()> {
+        let manifest = storage_stable::get_manifest(&model_id.0)
+            .map_err(|_| RepositoryError::new(ModelError::NotFound, "Model not found"))?;
+
+        if badge_type == BadgeType::HighCompression {
+            let threshold = storage_stable::get_high_compression_threshold();
+            let ratio = manifest.get_compression_ratio().unwrap_or(0.0);
+            if ratio < threshold {
+                return Err(RepositoryError::new(
+                    ModelError::VerificationFailed,
+                    format!("HighCompression requires a ratio of at least {}, model has {}", threshold, ratio),
+                ));
             }
         }
 
+        if badge_type == BadgeType::VerifiedQuant
+            && !storage_stable::get_verification(&model_id.0).is_some_and(|v| v.passed)
+        {
+            return Err(RepositoryError::new(
+                ModelError::VerificationFailed,
+                "VerifiedQuant requires a passing verify_model run",
+            ));
+        }
+
+        if badge_type == BadgeType::Reproducible
+            && storage_stable::get_reproducibility(&model_id.0).is_none()
+        {
+            return Err(RepositoryError::new(
+                ModelError::VerificationFailed,
+                "Reproducible requires a passing check_reproducible run",
+            ));
+        }
+
+        let mut badges = storage_stable::get_badges(&model_id.0);
+        if badges.iter().any(|b| b.badge_type == badge_type) {
+            return Err(RepositoryError::new(
+                ModelError::InvalidState,
+                format!("Model already holds badge {:?}", badge_type),
+            ));
+        }
+
+        badges.push(Badge {
+            badge_type: badge_type.clone(),
+            granted_at: time(),
+            granted_by: actor.clone(),
+            metadata,
+        });
+        storage_stable::set_badges(&model_id.0, &badges)
+            .map_err(storage_error("Badge store error"))?;
+
+        let event = AuditEvent {
+            event_type: AuditEventType::BadgeGrant,
+            model_id: model_id.clone(),
+            actor,
+            timestamp: time(),
+            details: format!("Badge {:?} granted", badge_type),
+        };
+        storage_stable::append_audit_event(&event).ok();
+        self.audit_log.push(event);
+
         Ok(())
     }
 
-    pub fn add_authorized_uploader(&mut self, uploader: String) {
-        if !self.authorized_uploaders.contains(&uploader) {
-            self.authorized_uploaders.push(uploader);
+    fn detach_badge(&mut self, model_id: &ModelId, badge_type: BadgeType, actor: String) -> RepositoryResult<()> {
+        let mut badges = storage_stable::get_badges(&model_id.0);
+        let before = badges.len();
+        badges.retain(|b| b.badge_type != badge_type);
+        if badges.len() == before {
+            return Err(RepositoryError::new(
+                ModelError::NotFound,
+                format!("Model does not hold badge {:?}", badge_type),
+            ));
         }
+
+        storage_stable::set_badges(&model_id.0, &badges)
+            .map_err(storage_error("Badge store error"))?;
+
+        let event = AuditEvent {
+            event_type: AuditEventType::BadgeGrant,
+            model_id: model_id.clone(),
+            actor,
+            timestamp: time(),
+            details: format!("Badge {:?} revoked", badge_type),
+        };
+        storage_stable::append_audit_event(&event).ok();
+        self.audit_log.push(event);
+
+        Ok(())
     }
 
-    pub fn get_audit_log(&self) -> &[AuditEvent] {
-        // Merge in-memory and stable log (stable is source of truth)
-        // For now, return in-memory if non-empty; else read stable
-        if !self.audit_log.is_empty() {
-            &self.audit_log
-        } else {
-            // This method signature returns a slice; for simplicity, ensure audit_log is hydrated
-            let stable_log = storage_stable::get_audit_log();
-            // Replace in-memory
-            // Note: This is a read method; hydration requires mutability outside. Keep as-is.
-            &self.audit_log
+    /// Reassemble a NOVAQ model from its stored chunks and check its
+    /// structure independently of the report submitted with it. Only the
+    /// structure is checked; the self-reported `bit_accuracy` is neither
+    /// re-derived nor repeated in the result. The outcome is recorded
+    /// either way; only a pass makes the model eligible for the
+    /// `VerifiedQuant` badge.
+    pub fn verify_model(&mut self, model_id: &ModelId, actor: String) -> RepositoryResult<StructureReport> {
+        let manifest = storage_stable::get_manifest(&model_id.0)
+            .map_err(|_| RepositoryError::new(ModelError::NotFound, "Model not found"))?;
+        if !matches!(manifest.compression_type, CompressionType::NOVAQ) {
+            return Err(RepositoryError::new(ModelError::InvalidState, "Only NOVAQ models can be verified"));
         }
+
+        let outcome = Self::decode_stored_novaq(&manifest).and_then(|model| {
+            validation::verify_novaq_structure(&model)?;
+            Ok(StructureReport {
+                subspaces: model.config.num_subspaces,
+                codebook_entries: model.vector_codebooks.iter().map(|c| c.len() as u64).sum(),
+                indices_checked: model.quantization_indices.iter().map(|i| i.len() as u64).sum(),
+            })
+        });
+
+        let details = match &outcome {
+            Ok(report) => format!(
+                "Structure verified: {} subspaces, {} codebook entries, {} indices",
+                report.subspaces, report.codebook_entries, report.indices_checked
+            ),
+            Err(e) => format!("Verification failed: {}", e),
+        };
+        let record = VerificationRecord {
+            passed: outcome.is_ok(),
+            verified_at: time(),
+            verified_by: actor.clone(),
+            details: details.clone(),
+        };
+        storage_stable::store_verification(&model_id.0, &record)
+            .map_err(storage_error("Verification store error"))?;
+
+        let event = AuditEvent {
+            event_type: AuditEventType::Verification,
+            model_id: model_id.clone(),
+            actor,
+            timestamp: time(),
+            details,
+        };
+        storage_stable::append_audit_event(&event).ok();
+        self.audit_log.push(event);
+
+        outcome.map_err(|e| RepositoryError::new(ModelError::VerificationFailed, format!("Verification failed: {}", e)))
     }
-}
\ No newline at end of file
+
+    /// Compare two NOVAQ uploads of the same source model. They are
+    /// reproducible if bit-identical, or if configs (seed included),
+    /// weight shapes and indices match and every codebook value is within
+    /// `REPRODUCIBILITY_TOLERANCE`. A reproducible report is stored on both
+    /// models and makes them eligible for the `Reproducible` badge.
+    pub fn check_reproducible(
+        &mut self,
+        model_a: &ModelId,
+        model_b: &ModelId,
+        actor: String,
+    ) -> RepositoryResult<ReproducibilityReport> {
+        if model_a.0 == model_b.0 {
+            return Err(RepositoryError::new(ModelError::InvalidFormat, "Compare two different models"));
+        }
+        let manifest_a = storage_stable::get_manifest(&model_a.0)
+            .map_err(|_| RepositoryError::new(ModelError::NotFound, format!("Model {} not found", model_a.0)))?;
+        let manifest_b = storage_stable::get_manifest(&model_b.0)
+            .map_err(|_| RepositoryError::new(ModelError::NotFound, format!("Model {} not found", model_b.0)))?;
+        for manifest in [&manifest_a, &manifest_b] {
+            if !matches!(manifest.compression_type, CompressionType::NOVAQ) {
+                return Err(RepositoryError::new(ModelError::InvalidState, format!(
+                    "Model {} is not a NOVAQ model", manifest.model_id.0
+                )));
+            }
+        }
+
+        let decode = |manifest: &ModelManifest| Self::decode_stored_novaq(manifest)
+            .map_err(|e| RepositoryError::new(ModelError::InvalidFormat, format!("Model {}: {}", manifest.model_id.0, e)));
+        let novaq_a = decode(&manifest_a)?;
+        let novaq_b = decode(&manifest_b)?;
+
+        let mut details = Vec::new();
+        let digest_match = manifest_a.digest == manifest_b.digest;
+        let config_match = novaq_a.config == novaq_b.config;
+        if !config_match {
+            details.push("NOVAQ configs differ".to_string());
+        }
+        let shapes_match = novaq_a.weight_shapes == novaq_b.weight_shapes;
+        if !shapes_match {
+            details.push("Weight shapes differ".to_string());
+        }
+        let indices_match = novaq_a.quantization_indices == novaq_b.quantization_indices;
+        if !indices_match {
+            details.push("Quantization indices differ".to_string());
+        }
+        let max_codebook_delta = validation::max_codebook_delta(&novaq_a, &novaq_b);
+        match max_codebook_delta {
+            None => details.push("Codebook shapes differ".to_string()),
+            Some(delta) if delta > validation::REPRODUCIBILITY_TOLERANCE => {
+                details.push(format!("Codebooks differ by up to {}", delta));
+            }
+            Some(_) => {}
+        }
+
+        let numerically_close = config_match
+            && shapes_match
+            && indices_match
+            && max_codebook_delta.is_some_and(|d| d <= validation::REPRODUCIBILITY_TOLERANCE);
+        let report = ReproducibilityReport {
+            model_a: model_a.clone(),
+            model_b: model_b.clone(),
+            digest_match,
+            config_match,
+            max_codebook_delta,
+            bit_identical: digest_match,
+            numerically_close,
+            reproducible: digest_match || numerically_close,
+            checked_at: time(),
+            checked_by: actor.clone(),
+            details,
+        };
+
+        if report.reproducible {
+            for model_id in [model_a, model_b] {
+                storage_stable::store_reproducibility(&model_id.0, &report)
+                    .map_err(storage_error("Reproducibility store error"))?;
+            }
+        }
+
+        let event = AuditEvent {
+            event_type: AuditEventType::Verification,
+            model_id: model_a.clone(),
+            actor,
+            timestamp: time(),
+            details: format!(
+                "Reproducibility against {}: {}",
+                model_b.0,
+                if report.reproducible { "reproducible" } else { "not reproducible" }
+            ),
+        };
+        storage_stable::append_audit_event(&event).ok();
+        self.audit_log.push(event);
+
+        Ok(report)
+    }
+
+    fn decode_stored_novaq(manifest: &ModelManifest) -> Result<NOVAQModelCandid, String> {
+        let mut bytes = Vec::new();
+        for chunk in &manifest.chunks {
+            let data = storage_stable::get_chunk_for_model(&manifest.model_id.0, &chunk.id)
+                .map_err(|_| format!("Chunk {} missing", chunk.id))?;
+            bytes.extend_from_slice(&data);
+        }
+
+        bincode::deserialize::<NOVAQModelCandid>(&bytes)
+            .map_err(|e| format!("Stored bytes are not a NOVAQ model: {}", e))
+    }
+
+    /// Evaluate admin-configured activation gates against the latest
+    /// recorded value of each gated benchmark metric
+    fn check_activation_gates(&self, model_id: &ModelId) -> RepositoryResult<()> {
+        let gates = storage_stable::get_activation_gates();
+        if gates.is_empty() {
+            return Ok(());
+        }
+
+        let benchmarks = storage_stable::get_benchmarks(&model_id.0);
+        for gate in &gates {
+            let latest = benchmarks
+                .iter()
+                .filter(|b| b.metric == gate.metric)
+                .max_by_key(|b| b.recorded_at)
+                .ok_or_else(|| RepositoryError::new(
+                    ModelError::VerificationFailed,
+                    format!("Activation gate failed: no {} benchmark recorded", gate.metric),
+                ))?;
+
+            if !gate.is_satisfied_by(latest.value) {
+                return Err(RepositoryError::new(ModelError::VerificationFailed, format!(
+                    "Activation gate failed: {} = {} does not satisfy {:?} {}",
+                    gate.metric, latest.value, gate.comparison, gate.threshold
+                )));
+            }
+        }
+
+        Ok(())
+    }
+
+    pub fn register_uploader_key(
+        &mut self,
+        principal: String,
+        public_key: Vec<u8>,
+        actor: String,
+    ) -> RepositoryResult<()> {
+        require_role(&actor, Role::Admin, "Not authorized to register uploader keys")?;
+
+        if !storage_stable::has_role(&principal, &Role::Uploader) {
+            return Err(RepositoryError::new(ModelError::InvalidState, "Principal is not an authorized uploader"));
+        }
+
+        validation::parse_public_key(&public_key)
+            .map_err(|e| RepositoryError::new(ModelError::InvalidFormat, e))?;
+        storage_stable::set_uploader_key(&principal, public_key);
+
+        Ok(())
+    }
+
+    /// Set or clear a model's chunk access list; `None` makes it public
+    pub fn set_access_list(
+        &mut self,
+        model_id: &ModelId,
+        access_list: Option<Vec<String>>,
+        actor: String,
+    ) -> RepositoryResult<()> {
+        require_role(&actor, Role::Admin, "Not authorized to change model access")?;
+        storage_stable::get_manifest(&model_id.0)
+            .map_err(|_| RepositoryError::new(ModelError::NotFound, "Model not found"))?;
+
+        let access_list = access_list.map(|principals| {
+            let mut principals: Vec<String> = principals.into_iter()
+                .map(|p| p.trim().to_string())
+                .filter(|p| !p.is_empty())
+                .collect();
+            principals.sort();
+            principals.dedup();
+            principals
+        });
+        storage_stable::set_access_list(&model_id.0, access_list.as_ref())
+            .map_err(storage_error("Access list store error"))?;
+
+        let details = match &access_list {
+            Some(principals) => format!("Access restricted to {} principal(s): {}", principals.len(), principals.join(", ")),
+            None => "Access list cleared; model is public".to_string(),
+        };
+        let event = AuditEvent {
+            event_type: AuditEventType::AccessChange,
+            model_id: model_id.clone(),
+            actor,
+            timestamp: time(),
+            details,
+        };
+        storage_stable::append_audit_event(&event).ok();
+        self.audit_log.push(event);
+
+        Ok(())
+    }
+
+    /// Recompute the canonical payload of every signed model and check it
+    /// against the stored signature, reporting `(model_id, still_valid)`
+    pub fn revalidate_signatures(&self) -> Vec<(String, bool)> {
+        storage_stable::list_signed_models()
+            .into_iter()
+            .map(|model_id| {
+                let valid = match (
+                    storage_stable::get_signature(&model_id),
+                    storage_stable::get_manifest(&model_id),
+                ) {
+                    (Ok(record), Ok(manifest)) => storage_stable::get_uploader_key(&record.signer)
+                        .map(|key| {
+                            validation::verify_manifest_signature(&key, &manifest, &record.signature).is_ok()
+                        })
+                        .unwrap_or(false),
+                    _ => false,
+                };
+                (model_id, valid)
+            })
+            .collect()
+    }
+
+    /// Revoke a principal's upload access along with its signing key
+    pub fn remove_uploader(&mut self, principal: &str, actor: String) -> RepositoryResult<()> {
+        require_role(&actor, Role::Admin, "Not authorized to remove uploaders")?;
+
+        if !storage_stable::has_role(principal, &Role::Uploader) {
+            return Err(RepositoryError::new(ModelError::NotFound, "Principal is not an authorized uploader"));
+        }
+
+        // An admin removing themselves as the only uploader would leave no one able to upload
+        let uploaders = storage_stable::list_principals_with_role(&Role::Uploader);
+        if principal == actor && uploaders.len() == 1 {
+            return Err(RepositoryError::new(ModelError::InvalidState, "Cannot remove the last remaining uploader"));
+        }
+
+        storage_stable::revoke_role(principal, &Role::Uploader)
+            .map_err(storage_error("Role store error"))?;
+        storage_stable::remove_uploader_key(principal);
+
+        let event = AuditEvent {
+            event_type: AuditEventType::AccessChange,
+            model_id: ModelId(String::new()),
+            actor,
+            timestamp: time(),
+            details: format!("Uploader {} removed", principal),
+        };
+        storage_stable::append_audit_event(&event).ok();
+        self.audit_log.push(event);
+
+        Ok(())
+    }
+
+    pub fn get_manifest(&self, model_id: &ModelId) -> Option<&ModelManifest> {
+        self.models.get(&model_id.0)
+    }
+
+    /// Manifest read backing the `get_manifest` query; records nothing. With
+    /// audited reads on, private manifests are only served by `get_manifest_audited`.
+    pub fn read_manifest(&self, model_id: &ModelId) -> RepositoryResult<ModelManifest> {
+        if storage_stable::get_audited_reads() && storage_stable::get_access_list(&model_id.0).is_some() {
+            return Err(RepositoryError::new(
+                ModelError::InvalidState,
+                "Audited reads are enabled; use get_manifest_audited",
+            ));
+        }
+        storage_stable::get_manifest(&model_id.0)
+            .map_err(|_| RepositoryError::new(ModelError::NotFound, "Model not found"))
+    }
+
+    /// Manifest read for audited mode; must run in an update call so the
+    /// `ManifestAccess` event logged for a private model is persisted
+    pub fn get_manifest_audited(&mut self, model_id: &ModelId, actor: String) -> RepositoryResult<ModelManifest> {
+        let manifest = storage_stable::get_manifest(&model_id.0)
+            .map_err(|_| RepositoryError::new(ModelError::NotFound, "Model not found"))?;
+
+        if storage_stable::get_access_list(&model_id.0).is_some() {
+            let event = AuditEvent {
+                event_type: AuditEventType::ManifestAccess,
+                model_id: model_id.clone(),
+                actor,
+                timestamp: time(),
+                details: "Manifest of private model accessed".to_string(),
+            };
+            storage_stable::append_audit_event(&event).ok();
+            self.audit_log.push(event);
+        }
+
+        Ok(manifest)
+    }
+
+    /// Look up a model that `caller` may currently read: it must be Active,
+    /// past its `serve_after` time, and either public or list the caller
+    fn servable_model(&self, model_id: &ModelId, caller: &str) -> RepositoryResult<&ModelManifest> {
+        let model = self.models.get(&model_id.0)
+            .ok_or_else(|| RepositoryError::new(ModelError::NotFound, "Model not found"))?;
+        if !matches!(model.state, ModelState::Active) {
+            return Err(RepositoryError::new(ModelError::InvalidState, "Model is not active"));
+        }
+
+        if !model.is_servable_at(time()) {
+            return Err(RepositoryError::new(ModelError::InvalidState, format!(
+                "Model not yet available; servable after {}",
+                model.serve_after.unwrap_or_default()
+            )));
+        }
+
+        // Admins can always read, e.g. to verify a private model
+        if let Some(access_list) = storage_stable::get_access_list(&model_id.0) {
+            if !access_list.iter().any(|p| p == caller) && !storage_stable::has_role(caller, &Role::Admin) {
+                return Err(RepositoryError::new(ModelError::UnauthorizedAccess, "Caller is not on the model's access list"));
+            }
+        }
+
+        Ok(model)
+    }
+
+    /// Read-only chunk fetch backing the `get_chunk` query; records no audit
+    /// event. Accesses that must be counted or audited go through `get_chunk_audited`.
+    pub fn get_chunk(&self, model_id: &ModelId, chunk_id: &str, caller: &str) -> RepositoryResult<Vec<u8>> {
+        self.servable_model(model_id, caller)?;
+
+        storage_stable::get_chunk_for_model(&model_id.0, chunk_id)
+            .map_err(|_| RepositoryError::new(ModelError::NotFound, "Chunk not found"))
+    }
+
+    /// Like `get_chunk`, but re-hashes the stored bytes and fails with
+    /// `VerificationFailed` if they no longer match the manifest
+    pub fn get_chunk_verified(&self, model_id: &ModelId, chunk_id: &str, caller: &str) -> RepositoryResult<Vec<u8>> {
+        let model = self.servable_model(model_id, caller)?;
+        let info = model.chunks.iter()
+            .find(|c| c.id == chunk_id)
+            .ok_or_else(|| RepositoryError::new(ModelError::NotFound, "Chunk not in manifest"))?;
+
+        let data = storage_stable::get_chunk_for_model(&model_id.0, chunk_id)
+            .map_err(|_| RepositoryError::new(ModelError::NotFound, "Chunk not found"))?;
+        let actual = hex::encode(Sha256::digest(&data));
+        if !actual.eq_ignore_ascii_case(&info.sha256) {
+            return Err(RepositoryError::new(ModelError::VerificationFailed, format!(
+                "Chunk {} hash mismatch: expected {}, stored bytes hash to {}",
+                chunk_id, info.sha256, actual
+            )));
+        }
+
+        Ok(data)
+    }
+
+    /// Chunk infos in offset order for sequential download. A stored
+    /// manifest that no longer tiles contiguously is reported as corrupt.
+    pub fn get_download_plan(&self, model_id: &ModelId, caller: &str) -> RepositoryResult<Vec<ChunkInfo>> {
+        let model = self.servable_model(model_id, caller)?;
+
+        validation::ordered_chunks(model)
+            .map_err(|e| RepositoryError::new(ModelError::InvalidFormat, e))
+    }
+
+    /// Read `length` bytes starting at `offset` in the model's byte stream,
+    /// stitching together whichever chunks the range spans. `length` is
+    /// capped at `MAX_RANGE_BYTES` so the reply fits in a query response.
+    pub fn get_chunk_range(&self, model_id: &ModelId, offset: u64, length: u64, caller: &str) -> RepositoryResult<Vec<u8>> {
+        let model = self.servable_model(model_id, caller)?;
+
+        let total: u64 = model.chunks.iter().map(|c| c.offset + c.size).max().unwrap_or(0);
+        if offset >= total {
+            return Err(RepositoryError::new(ModelError::InvalidFormat, format!(
+                "Offset {} is beyond the end of the model ({} bytes)", offset, total
+            )));
+        }
+        let end = offset.saturating_add(length.min(MAX_RANGE_BYTES)).min(total);
+
+        let mut chunks: Vec<&ChunkInfo> = model.chunks.iter()
+            .filter(|c| c.offset < end && c.offset + c.size > offset)
+            .collect();
+        chunks.sort_by_key(|c| c.offset);
+
+        let mut data = Vec::with_capacity((end - offset) as usize);
+        for chunk in chunks {
+            let bytes = storage_stable::get_chunk_for_model(&model_id.0, &chunk.id)
+                .map_err(|_| RepositoryError::new(ModelError::NotFound, format!("Chunk {} not found", chunk.id)))?;
+            let from = offset.saturating_sub(chunk.offset) as usize;
+            let to = ((end - chunk.offset) as usize).min(bytes.len());
+            if from < to {
+                data.extend_from_slice(&bytes[from..to]);
+            }
+        }
+
+        Ok(data)
+    }
+
+    /// Fetch several chunks in order, stopping once `MAX_BATCH_BYTES` would
+    /// be exceeded. Missing chunks are `None`; a result shorter than
+    /// `chunk_ids` means the client should request the rest in a new batch.
+    pub fn batch_get_chunks(&self, model_id: &ModelId, chunk_ids: &[String], caller: &str) -> RepositoryResult<Vec<Option<Vec<u8>>>> {
+        self.servable_model(model_id, caller)?;
+
+        let mut results = Vec::with_capacity(chunk_ids.len());
+        let mut total: u64 = 0;
+        for chunk_id in chunk_ids {
+            let data = storage_stable::get_chunk_for_model(&model_id.0, chunk_id).ok();
+            let size = data.as_ref().map_or(0, |d| d.len() as u64);
+            if !results.is_empty() && total + size > MAX_BATCH_BYTES {
+                break;
+            }
+            total += size;
+            results.push(data);
+        }
+
+        Ok(results)
+    }
+
+    /// Batch fetch for audited reads; logs one `ChunkAccess` event for the batch
+    pub fn batch_get_chunks_audited(
+        &mut self,
+        model_id: &ModelId,
+        chunk_ids: &[String],
+        actor: String,
+    ) -> RepositoryResult<Vec<Option<Vec<u8>>>> {
+        let results = self.batch_get_chunks(model_id, chunk_ids, &actor)?;
+        let access_count = storage_stable::increment_access_count(&model_id.0);
+
+        let served = results.iter().filter(|r| r.is_some()).count();
+        let details = format!("Batch of {} chunks accessed ({} requested, {} missing)",
+            served, chunk_ids.len(), results.len() - served);
+        self.log_chunk_access(model_id, actor, access_count, details);
+
+        Ok(results)
+    }
+
+    /// Chunk fetch for audited reads; must run in an update call so the
+    /// `ChunkAccess` event and access count are persisted. This is the
+    /// authoritative record of chunk access.
+    pub fn get_chunk_audited(&mut self, model_id: &ModelId, chunk_id: &str, actor: String) -> RepositoryResult<Vec<u8>> {
+        let data = self.get_chunk(model_id, chunk_id, &actor)?;
+        let access_count = storage_stable::increment_access_count(&model_id.0);
+
+        self.log_chunk_access(model_id, actor, access_count, format!("Chunk {} accessed", chunk_id));
+
+        Ok(data)
+    }
+
+    /// Log a `ChunkAccess` event for the first of every N audited reads of
+    /// the model, N being the sampling rate. Access counts stay exact.
+    fn log_chunk_access(&mut self, model_id: &ModelId, actor: String, access_count: u64, details: String) {
+        let rate = storage_stable::get_audit_sampling() as u64;
+        if !access_count.saturating_sub(1).is_multiple_of(rate.max(1)) {
+            return;
+        }
+        let event = AuditEvent {
+            event_type: AuditEventType::ChunkAccess,
+            model_id: model_id.clone(),
+            actor,
+            timestamp: time(),
+            details: if rate > 1 {
+                format!("{} (sampled 1 in {})", details, rate)
+            } else {
+                details
+            },
+        };
+        storage_stable::append_audit_event(&event).ok();
+        self.audit_log.push(event);
+    }
+
+    pub fn list_models(&self, state_filter: Option<ModelState>) -> Vec<&ModelManifest> {
+        self.models
+            .values()
+            .filter(|m| {
+                if let Some(ref filter_state) = state_filter {
+                    std::mem::discriminant(&m.state) == std::mem::discriminant(filter_state)
+                } else {
+                    true
+                }
+            })
+            .collect()
+    }
+
+    /// Remove chunks written by a failed upload so none are left orphaned
+    fn rollback_chunks(namespace: &str, chunk_ids: &[&str]) {
+        for chunk_id in chunk_ids {
+            storage_stable::remove_chunk_for_model(namespace, chunk_id);
+        }
+    }
+
+    fn verify_upload_signature(&self, upload: &ModelUpload, actor: &str) -> Result<(), String> {
+        let signature = upload.signature.as_ref()
+            .ok_or("Upload must be signed")?;
+        let public_key = storage_stable::get_uploader_key(actor)
+            .ok_or("No public key registered for uploader")?;
+
+        validation::verify_manifest_signature(&public_key, &upload.manifest, signature)
+    }
+
+    fn validate_manifest(&self, manifest: &ModelManifest) -> Result<(), String> {
+        if manifest.model_id.0.starts_with(storage_stable::STAGING_PREFIX) {
+            return Err(format!("Model ids may not start with {}", storage_stable::STAGING_PREFIX));
+        }
+        if manifest.chunks.is_empty() {
+            return Err("Manifest must contain at least one chunk".to_string());
+        }
+
+        let max_chunk_size = storage_stable::get_max_chunk_size();
+        let mut seen_ids = std::collections::HashSet::new();
+        for chunk in &manifest.chunks {
+            if chunk.size > max_chunk_size {
+                return Err(format!("Chunk {} exceeds the {} byte limit", chunk.id, max_chunk_size));
+            }
+            if !seen_ids.insert(chunk.id.as_str()) {
+                return Err(format!("Duplicate chunk id {}", chunk.id));
+            }
+        }
+
+        // Chunks must tile [0, total) with no gaps or overlaps
+        validation::ordered_chunks(manifest)?;
+
+        // Every compressed type must carry its compression figures
+        if manifest.is_quantized() {
+            let info = manifest.compression_info()
+                .ok_or_else(|| format!("{:?} manifest is missing compression info", manifest.compression_type))?;
+            if !info.compression_ratio.is_finite() || info.compression_ratio <= 0.0 {
+                return Err(format!("Invalid compression ratio {}", info.compression_ratio));
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Rebuild the in-memory mirror of manifests and the audit log from
+    /// stable storage, e.g. after an upgrade wiped the heap
+    pub fn load_from_stable(&mut self) {
+        self.models = storage_stable::list_models()
+            .into_iter()
+            .filter_map(|id| storage_stable::get_manifest(&id).ok().map(|m| (id, m)))
+            .collect();
+        self.audit_log = storage_stable::get_audit_log();
+        self.governance_enabled = storage_stable::get_governance_enabled();
+    }
+
+    /// Pending models, oldest upload first
+    pub fn pending_queue(&self, now: u64) -> Vec<PendingModelSummary> {
+        let mut queue: Vec<PendingModelSummary> = self.models.values()
+            .filter(|m| matches!(m.state, ModelState::Pending))
+            .map(|model| {
+                let meta = storage_stable::get_model_meta(&model.model_id.0).ok();
+                PendingModelSummary {
+                    model_id: model.model_id.clone(),
+                    version: model.version.clone(),
+                    uploader: model.uploader.clone(),
+                    uploaded_at: model.uploaded_at,
+                    age_ns: now.saturating_sub(model.uploaded_at),
+                    compression_type: model.compression_type.clone(),
+                    family: meta.as_ref().map(|m| m.family.clone()),
+                    arch: meta.map(|m| m.arch),
+                }
+            })
+            .collect();
+        queue.sort_by(|a, b| a.uploaded_at.cmp(&b.uploaded_at).then_with(|| a.model_id.0.cmp(&b.model_id.0)));
+        queue
+    }
+
+    /// Models uploaded by `principal` and their total chunk bytes
+    pub fn upload_usage(&self, principal: &str) -> UploadUsage {
+        let owned: Vec<&ModelManifest> = self.models.values()
+            .filter(|m| m.uploader.as_deref() == Some(principal))
+            .collect();
+        UploadUsage {
+            principal: principal.to_string(),
+            quota: storage_stable::get_upload_quota(principal),
+            models: owned.len() as u64,
+            bytes: owned.iter().map(|m| m.total_chunk_bytes()).sum(),
+        }
+    }
+
+    fn check_upload_quota(&self, actor: &str, new_bytes: u64) -> RepositoryResult<()> {
+        let usage = self.upload_usage(actor);
+        if usage.quota.max_models.is_some_and(|max| usage.models + 1 > max) {
+            return Err(RepositoryError::new(ModelError::QuotaExceeded, format!(
+                "Upload quota of {} models reached", usage.quota.max_models.unwrap_or(0)
+            )));
+        }
+        if usage.quota.max_bytes.is_some_and(|max| usage.bytes.saturating_add(new_bytes) > max) {
+            return Err(RepositoryError::new(ModelError::QuotaExceeded, format!(
+                "Upload of {} bytes would exceed the {} byte quota ({} in use)",
+                new_bytes, usage.quota.max_bytes.unwrap_or(0), usage.bytes
+            )));
+        }
+        Ok(())
+    }
+
+    /// Set or (with None) clear a principal's quota override
+    pub fn set_upload_quota(&mut self, principal: &str, quota: Option<UploadQuota>, actor: String) -> RepositoryResult<()> {
+        require_role(&actor, Role::Admin, "Not authorized to set upload quotas")?;
+        storage_stable::set_upload_quota(principal, quota.as_ref())
+            .map_err(storage_error("Quota store error"))
+    }
+
+    /// Compare the in-memory mirror against stable storage
+    pub fn repository_state(&self) -> RepoStateReport {
+        let stable: HashMap<String, ModelManifest> = storage_stable::list_models()
+            .into_iter()
+            .filter_map(|id| storage_stable::get_manifest(&id).ok().map(|m| (id, m)))
+            .collect();
+
+        let mut missing_in_memory: Vec<ModelId> = stable.keys()
+            .filter(|id| !self.models.contains_key(*id))
+            .map(|id| ModelId(id.clone()))
+            .collect();
+        let mut missing_in_stable = Vec::new();
+        let mut state_mismatches = Vec::new();
+        for (id, model) in &self.models {
+            match stable.get(id) {
+                None => missing_in_stable.push(ModelId(id.clone())),
+                Some(stored) if std::mem::discriminant(&stored.state) != std::mem::discriminant(&model.state) => {
+                    state_mismatches.push((ModelId(id.clone()), model.state.clone(), stored.state.clone()));
+                }
+                Some(_) => {}
+            }
+        }
+        missing_in_memory.sort_by(|a, b| a.0.cmp(&b.0));
+        missing_in_stable.sort_by(|a, b| a.0.cmp(&b.0));
+        state_mismatches.sort_by(|a, b| a.0.0.cmp(&b.0.0));
+
+        let in_sync = missing_in_memory.is_empty() && missing_in_stable.is_empty() && state_mismatches.is_empty();
+        RepoStateReport {
+            memory_models: self.models.len() as u64,
+            stable_models: stable.len() as u64,
+            missing_in_memory,
+            missing_in_stable,
+            state_mismatches,
+            in_sync,
+        }
+    }
+
+    /// Throw away the in-memory mirror and rebuild it from stable storage
+    pub fn resync_from_stable(&mut self, actor: &str) -> RepositoryResult<ResyncReport> {
+        require_role(actor, Role::Admin, "Not authorized to resync")?;
+
+        let before = self.repository_state();
+        self.load_from_stable();
+        Ok(ResyncReport {
+            models_loaded: self.models.len() as u64,
+            audit_events_loaded: self.audit_log.len() as u64,
+            reconciled: (before.missing_in_memory.len()
+                + before.missing_in_stable.len()
+                + before.state_mismatches.len()) as u64,
+        })
+    }
+
+    pub fn get_audit_log(&self) -> &[AuditEvent] {
+        // In-memory mirror, rehydrated from stable storage on upgrade
+        &self.audit_log
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::infra::clock::advance;
+    use crate::services::governance::{GovernanceEngine, ProposalStatus, Vote};
+    use ed25519_dalek::{Signer, SigningKey};
+
+    const ADMIN: &str = "admin";
+    const UPLOADER: &str = "uploader";
+
+    fn signing_key() -> SigningKey {
+        SigningKey::from_bytes(&[7; 32])
+    }
+
+    fn register(principal: &str, roles: &[Role]) {
+        for role in roles {
+            storage_stable::grant_role(principal, role.clone()).unwrap();
+        }
+        storage_stable::set_uploader_key(principal, signing_key().verifying_key().to_bytes().to_vec());
+    }
+
+    fn meta(family: &str) -> ModelMeta {
+        ModelMeta {
+            family: family.to_string(),
+            arch: "transformer".to_string(),
+            tokenizer_id: "bpe".to_string(),
+            vocab_size: 32_000,
+            ctx_window: 4096,
+            license: "MIT".to_string(),
+            quantization_info: QuantizationInfo {
+                method: "none".to_string(),
+                quantizer_version: "1".to_string(),
+                quantization_date: 0,
+                source_model: family.to_string(),
+            },
+        }
+    }
+
+    fn sign(manifest: &mut ModelManifest) -> String {
+        manifest.digest = validation::calculate_manifest_digest(manifest);
+        let digest = hex::decode(&manifest.digest).unwrap();
+        hex::encode(signing_key().sign(&digest).to_bytes())
+    }
+
+    /// A signed, uncompressed upload whose chunks are `chunk-0`, `chunk-1`, ...
+    fn upload(model_id: &str, chunks: &[&[u8]]) -> ModelUpload {
+        let mut offset = 0;
+        let infos = chunks.iter().enumerate().map(|(i, data)| {
+            let info = ChunkInfo {
+                id: format!("chunk-{}", i),
+                offset,
+                size: data.len() as u64,
+                sha256: hex::encode(Sha256::digest(data)),
+                codec: None,
+                stored_size: None,
+            };
+            offset += data.len() as u64;
+            info
+        }).collect();
+        let mut manifest = ModelManifest {
+            model_id: ModelId(model_id.to_string()),
+            version: "1.0".to_string(),
+            chunks: infos,
+            digest: String::new(),
+            state: ModelState::Pending,
+            uploaded_at: 0,
+            uploader: None,
+            activated_at: None,
+            deprecated_at: None,
+            serve_after: None,
+            supersedes: None,
+            compression_type: CompressionType::Uncompressed,
+            compression: None,
+            novaq_config: None,
+            quantized_model: None,
+        };
+        let signature = sign(&mut manifest);
+        ModelUpload {
+            model_id: ModelId(model_id.to_string()),
+            manifest,
+            meta: meta("llama"),
+            chunks: chunks.iter().enumerate()
+                .map(|(i, data)| ChunkData { chunk_id: format!("chunk-{}", i), data: data.to_vec() })
+                .collect(),
+            signature: Some(signature),
+            verification_report: None,
+            idempotency_key: None,
+            overwrite: None,
+            compress_chunks: Some(false),
+        }
+    }
+
+    fn chunk(model_id: &str, chunk_id: &str) -> Option<Vec<u8>> {
+        storage_stable::get_chunk_for_model(model_id, chunk_id).ok()
+    }
+
+    fn badge(badge_type: BadgeType) -> Badge {
+        Badge { badge_type, granted_at: 0, granted_by: ADMIN.to_string(), metadata: None }
+    }
+
+    /// A repository with governance off, so admins activate directly
+    fn direct_repo() -> ModelRepository {
+        register(ADMIN, &[Role::Admin, Role::Uploader]);
+        let mut repo = ModelRepository::new();
+        repo.set_governance_enabled(false, ADMIN.to_string()).unwrap();
+        repo
+    }
+
+    fn submit(repo: &mut ModelRepository, model_id: &str) {
+        repo.submit_model(upload(model_id, &[model_id.as_bytes()]), ADMIN.to_string()).unwrap();
+    }
+
+    #[test]
+    fn resubmitting_an_existing_model_without_overwrite_is_rejected() {
+        register(UPLOADER, &[Role::Uploader]);
+        let mut repo = ModelRepository::new();
+        repo.submit_model(upload("m1", &[b"alpha", b"beta"]), UPLOADER.to_string()).unwrap();
+
+        let err = repo.submit_model(upload("m1", &[b"gamma"]), UPLOADER.to_string()).unwrap_err();
+        assert!(matches!(err.kind, ModelError::AlreadyExists));
+
+        // Asking for an overwrite still needs the admin role
+        let mut overwrite = upload("m1", &[b"gamma"]);
+        overwrite.overwrite = Some(true);
+        let err = repo.submit_model(overwrite, UPLOADER.to_string()).unwrap_err();
+        assert!(matches!(err.kind, ModelError::UnauthorizedAccess));
+
+        assert_eq!(chunk("m1", "chunk-0").as_deref(), Some(&b"alpha"[..]));
+        assert_eq!(chunk("m1", "chunk-1").as_deref(), Some(&b"beta"[..]));
+    }
+
+    #[test]
+    fn upload_id_must_match_the_manifest_id() {
+        register(ADMIN, &[Role::Admin, Role::Uploader]);
+        register(UPLOADER, &[Role::Uploader]);
+        let mut repo = ModelRepository::new();
+        repo.submit_model(upload("victim", &[b"alpha"]), ADMIN.to_string()).unwrap();
+
+        // A manifest for one model must not write chunks under another
+        let mut mismatched = upload("mine", &[b"evil"]);
+        mismatched.model_id = ModelId("victim".to_string());
+        let err = repo.submit_model(mismatched, UPLOADER.to_string()).unwrap_err();
+        assert!(matches!(err.kind, ModelError::InvalidFormat));
+
+        assert_eq!(chunk("victim", "chunk-0").as_deref(), Some(&b"alpha"[..]));
+        assert!(chunk("mine", "chunk-0").is_none());
+        assert!(storage_stable::get_manifest("mine").is_err());
+    }
+
+    #[test]
+    fn explicit_overwrite_replaces_chunks_and_revokes_verification_badges() {
+        register(ADMIN, &[Role::Admin, Role::Uploader]);
+        let mut repo = ModelRepository::new();
+        repo.submit_model(upload("m1", &[b"alpha", b"beta", b"gamma"]), ADMIN.to_string()).unwrap();
+        storage_stable::set_badges("m1", &vec![
+            badge(BadgeType::VerifiedQuant),
+            badge(BadgeType::Reproducible),
+            badge(BadgeType::CommunityTested),
+        ]).unwrap();
+
+        let mut overwrite = upload("m1", &[b"delta", b"beta"]);
+        overwrite.overwrite = Some(true);
+        repo.submit_model(overwrite, ADMIN.to_string()).unwrap();
+
+        assert_eq!(chunk("m1", "chunk-0").as_deref(), Some(&b"delta"[..]));
+        assert_eq!(chunk("m1", "chunk-1").as_deref(), Some(&b"beta"[..]));
+        assert_eq!(chunk("m1", "chunk-2"), None);
+        let staged = storage_stable::staging_model_id("m1");
+        assert!(!storage_stable::has_chunk_for_model(&staged, "chunk-0"));
+        assert!(!storage_stable::has_chunk_for_model(&staged, "chunk-1"));
+        assert_eq!(storage_stable::get_stored_bytes(), (b"delta".len() + b"beta".len()) as u64);
+
+        let badges: Vec<BadgeType> = storage_stable::get_badges("m1").into_iter().map(|b| b.badge_type).collect();
+        assert_eq!(badges, vec![BadgeType::CommunityTested]);
+        assert!(storage_stable::query_models_by_badge(&BadgeType::VerifiedQuant).is_empty());
+        assert!(storage_stable::query_models_by_badge(&BadgeType::Reproducible).is_empty());
+        assert_eq!(storage_stable::query_models_by_badge(&BadgeType::CommunityTested).len(), 1);
+    }
+
+    #[test]
+    fn deprecates_a_model_that_only_exists_in_stable_storage() {
+        register(ADMIN, &[Role::Admin]);
+        let mut manifest = upload("m1", &[b"alpha"]).manifest;
+        manifest.state = ModelState::Active;
+        storage_stable::store_manifest("m1", &manifest).unwrap();
+
+        // The in-memory mirror was never populated
+        let mut repo = ModelRepository::new();
+        let model = ModelId("m1".to_string());
+        assert!(repo.get_manifest(&model).is_none());
+
+        repo.deprecate_model(&model, ADMIN.to_string()).unwrap();
+        let stored = storage_stable::get_manifest("m1").unwrap();
+        assert!(matches!(stored.state, ModelState::Deprecated));
+        assert_eq!(stored.deprecated_at, Some(time()));
+
+        let err = repo.deprecate_model(&model, ADMIN.to_string()).unwrap_err();
+        assert!(matches!(err.kind, ModelError::InvalidState));
+    }
+
+    #[test]
+    fn failed_overwrite_leaves_the_original_chunks_intact() {
+        register(ADMIN, &[Role::Admin, Role::Uploader]);
+        let mut repo = ModelRepository::new();
+        let original = upload("m1", &[b"alpha", b"beta"]);
+        let digest = original.manifest.digest.clone();
+        repo.submit_model(original, ADMIN.to_string()).unwrap();
+        let stored = storage_stable::get_stored_bytes();
+
+        // Room for the first replacement chunk but not the second
+        storage_stable::set_storage_capacity(Some(stored + b"delta".len() as u64)).unwrap();
+        let mut overwrite = upload("m1", &[b"delta", b"epsilon"]);
+        overwrite.overwrite = Some(true);
+        let err = repo.submit_model(overwrite, ADMIN.to_string()).unwrap_err();
+        assert!(matches!(err.kind, ModelError::StorageFull));
+
+        assert_eq!(chunk("m1", "chunk-0").as_deref(), Some(&b"alpha"[..]));
+        assert_eq!(chunk("m1", "chunk-1").as_deref(), Some(&b"beta"[..]));
+        assert!(!storage_stable::has_chunk_for_model(&storage_stable::staging_model_id("m1"), "chunk-0"));
+        assert_eq!(storage_stable::get_stored_bytes(), stored);
+        assert_eq!(storage_stable::get_manifest("m1").unwrap().digest, digest);
+    }
+
+    #[test]
+    fn direct_activation_still_requires_an_activation_role() {
+        let mut repo = direct_repo();
+        register(UPLOADER, &[Role::Uploader]);
+        register("auditor", &[Role::Auditor]);
+        submit(&mut repo, "m1");
+        let model = ModelId("m1".to_string());
+
+        for caller in ["2vxsx-fae", "auditor"] {
+            let err = repo.activate_model(&model, Some(0), caller.to_string()).unwrap_err();
+            assert!(matches!(err.kind, ModelError::UnauthorizedAccess));
+        }
+        assert!(matches!(storage_stable::get_manifest("m1").unwrap().state, ModelState::Pending));
+
+        repo.activate_model(&model, None, UPLOADER.to_string()).unwrap();
+        assert!(matches!(storage_stable::get_manifest("m1").unwrap().state, ModelState::Active));
+    }
+
+    /// An engine where "v1" and "v2" are the only voters
+    fn two_voter_engine() -> GovernanceEngine {
+        let mut engine = GovernanceEngine::new();
+        engine.add_authorized_voter("v1".to_string(), None).unwrap();
+        engine.add_authorized_voter("v2".to_string(), None).unwrap();
+        engine
+    }
+
+    /// Open an activation proposal for `model_id` and have both voters pass it
+    fn pass_activation(repo: &ModelRepository, engine: &mut GovernanceEngine, model_id: &ModelId) -> u64 {
+        repo.check_activation_request(model_id, ADMIN).unwrap();
+        let proposal_id = engine.request_activation(model_id.clone(), ADMIN.to_string(), time()).unwrap();
+        engine.cast_vote(proposal_id, "v1".to_string(), Vote::Yes, time()).unwrap();
+        engine.cast_vote(proposal_id, "v2".to_string(), Vote::Yes, time()).unwrap();
+        assert_eq!(engine.tally_votes(proposal_id, time()).unwrap(), ProposalStatus::Passed);
+        proposal_id
+    }
+
+    #[test]
+    fn an_executed_activation_proposal_activates_the_model() {
+        register(ADMIN, &[Role::Admin, Role::Uploader]);
+        let mut repo = ModelRepository::new();
+        assert!(repo.governance_enabled());
+        let mut engine = two_voter_engine();
+        submit(&mut repo, "m1");
+        let model = ModelId("m1".to_string());
+
+        let proposal_id = pass_activation(&repo, &mut engine, &model);
+        assert!(matches!(storage_stable::get_manifest("m1").unwrap().state, ModelState::Pending));
+
+        // Apply before marking executed, as the execute_proposal endpoint does
+        let action = engine.execution_action(proposal_id).unwrap();
+        repo.apply_governance_action(action, "v1".to_string()).unwrap();
+        engine.execute_proposal(proposal_id).unwrap();
+
+        assert!(matches!(storage_stable::get_manifest("m1").unwrap().state, ModelState::Active));
+        assert_eq!(engine.get_proposal(proposal_id).unwrap().status, ProposalStatus::Executed);
+        assert!(engine.execution_action(proposal_id).is_err());
+    }
+
+    #[test]
+    fn a_failed_governance_action_leaves_the_proposal_executable() {
+        register(ADMIN, &[Role::Admin, Role::Uploader]);
+        let mut repo = ModelRepository::new();
+        let mut engine = two_voter_engine();
+        submit(&mut repo, "m1");
+        let model = ModelId("m1".to_string());
+        let proposal_id = pass_activation(&repo, &mut engine, &model);
+
+        storage_stable::remove_chunk_for_model("m1", "chunk-0");
+        let action = engine.execution_action(proposal_id).unwrap();
+        let err = repo.apply_governance_action(action, "v1".to_string()).unwrap_err();
+        assert!(matches!(err.kind, ModelError::VerificationFailed));
+        assert!(matches!(storage_stable::get_manifest("m1").unwrap().state, ModelState::Pending));
+        assert_eq!(engine.get_proposal(proposal_id).unwrap().status, ProposalStatus::Passed);
+
+        // Once the chunk is back the same proposal can still be executed
+        storage_stable::store_chunk_for_model("m1", "chunk-0", b"m1".to_vec(), None).unwrap();
+        let action = engine.execution_action(proposal_id).unwrap();
+        repo.apply_governance_action(action, "v1".to_string()).unwrap();
+        engine.execute_proposal(proposal_id).unwrap();
+        assert!(matches!(storage_stable::get_manifest("m1").unwrap().state, ModelState::Active));
+    }
+
+    fn gate(metric: &str, comparison: GateComparison, threshold: f32) -> ActivationGate {
+        ActivationGate { metric: metric.to_string(), comparison, threshold }
+    }
+
+    #[test]
+    fn activation_is_blocked_by_a_failing_benchmark_gate() {
+        let mut repo = direct_repo();
+        submit(&mut repo, "m1");
+        storage_stable::set_activation_gates(&vec![
+            gate("perplexity", GateComparison::AtMost, 10.0),
+            gate("accuracy", GateComparison::AtLeast, 0.8),
+        ]).unwrap();
+        let model = ModelId("m1".to_string());
+
+        // A required benchmark that was never recorded blocks activation
+        repo.record_benchmark(&model, "perplexity".to_string(), 8.5, ADMIN.to_string()).unwrap();
+        let err = repo.activate_model(&model, None, ADMIN.to_string()).unwrap_err();
+        assert!(matches!(err.kind, ModelError::VerificationFailed));
+        assert!(err.message.contains("no accuracy benchmark"));
+
+        repo.record_benchmark(&model, "accuracy".to_string(), 0.6, ADMIN.to_string()).unwrap();
+        let err = repo.activate_model(&model, None, ADMIN.to_string()).unwrap_err();
+        assert!(matches!(err.kind, ModelError::VerificationFailed));
+        assert!(matches!(storage_stable::get_manifest("m1").unwrap().state, ModelState::Pending));
+    }
+
+    #[test]
+    fn activation_is_allowed_when_every_gate_passes() {
+        let mut repo = direct_repo();
+        submit(&mut repo, "m1");
+        storage_stable::set_activation_gates(&vec![
+            gate("perplexity", GateComparison::AtMost, 10.0),
+            gate("accuracy", GateComparison::AtLeast, 0.8),
+        ]).unwrap();
+        let model = ModelId("m1".to_string());
+
+        repo.record_benchmark(&model, "perplexity".to_string(), 10.0, ADMIN.to_string()).unwrap();
+        repo.record_benchmark(&model, "accuracy".to_string(), 0.5, ADMIN.to_string()).unwrap();
+        // Only the latest value of each metric counts
+        advance(1);
+        repo.record_benchmark(&model, "accuracy".to_string(), 0.9, ADMIN.to_string()).unwrap();
+
+        repo.activate_model(&model, None, ADMIN.to_string()).unwrap();
+        assert!(matches!(storage_stable::get_manifest("m1").unwrap().state, ModelState::Active));
+    }
+
+    #[test]
+    fn revalidation_reports_models_whose_signed_fields_a_migration_changed() {
+        let mut repo = direct_repo();
+        submit(&mut repo, "m1");
+        submit(&mut repo, "m2");
+
+        // A migration that rewrites a digest-covered field of m1's chunks
+        let mut migrated = storage_stable::get_manifest("m1").unwrap();
+        migrated.chunks[0].sha256 = hex::encode(Sha256::digest(b"rewritten"));
+        storage_stable::store_manifest("m1", &migrated).unwrap();
+
+        let mut report = repo.revalidate_signatures();
+        report.sort();
+        assert_eq!(report, vec![("m1".to_string(), false), ("m2".to_string(), true)]);
+    }
+
+    #[test]
+    fn chunks_are_not_served_before_serve_after() {
+        const HOUR_NS: u64 = 60 * 60 * 1_000_000_000;
+        let mut repo = direct_repo();
+        submit(&mut repo, "m1");
+        let model = ModelId("m1".to_string());
+        repo.activate_model(&model, Some(time() + HOUR_NS), ADMIN.to_string()).unwrap();
+
+        // Listed as active, but not yet serving
+        assert!(matches!(repo.get_manifest(&model).unwrap().state, ModelState::Active));
+        let err = repo.get_chunk(&model, "chunk-0", "reader").unwrap_err();
+        assert!(matches!(err.kind, ModelError::InvalidState));
+        assert!(err.message.contains("not yet available"));
+        assert!(repo.get_chunk_verified(&model, "chunk-0", "reader").is_err());
+
+        advance(HOUR_NS);
+        assert_eq!(repo.get_chunk(&model, "chunk-0", "reader").unwrap(), b"m1");
+        assert_eq!(repo.get_chunk_verified(&model, "chunk-0", "reader").unwrap(), b"m1");
+    }
+
+    #[test]
+    fn deprecation_works_after_an_upgrade_cycle() {
+        let mut repo = direct_repo();
+        submit(&mut repo, "m1");
+        let model = ModelId("m1".to_string());
+        repo.activate_model(&model, None, ADMIN.to_string()).unwrap();
+
+        // An upgrade wipes the heap; post_upgrade rebuilds from stable memory
+        drop(repo);
+        let mut repo = ModelRepository::new();
+        repo.load_from_stable();
+        assert!(repo.get_manifest(&model).is_some());
+        assert!(!repo.governance_enabled());
+
+        repo.deprecate_model(&model, ADMIN.to_string()).unwrap();
+        assert!(matches!(storage_stable::get_manifest("m1").unwrap().state, ModelState::Deprecated));
+        assert!(matches!(repo.get_manifest(&model).unwrap().state, ModelState::Deprecated));
+    }
+
+    #[test]
+    fn failed_upload_leaves_no_chunks_or_refcounts_behind() {
+        register(UPLOADER, &[Role::Uploader]);
+        let mut repo = ModelRepository::new();
+        let chunks: [&[u8]; 3] = [b"alpha", b"beta", b"gamma"];
+
+        // The first two chunks fit, the third does not
+        storage_stable::set_storage_capacity(Some((b"alpha".len() + b"beta".len()) as u64)).unwrap();
+        let err = repo.submit_model(upload("m1", &chunks), UPLOADER.to_string()).unwrap_err();
+        assert!(matches!(err.kind, ModelError::StorageFull));
+
+        for (i, data) in chunks.iter().enumerate() {
+            assert!(!storage_stable::has_chunk_for_model("m1", &format!("chunk-{}", i)));
+            assert_eq!(storage_stable::blob_refcount(&hex::encode(Sha256::digest(data))), 0);
+        }
+        assert_eq!(storage_stable::get_stored_bytes(), 0);
+        assert!(storage_stable::get_manifest("m1").is_err());
+    }
+
+    #[test]
+    fn tampered_chunk_hash_is_rejected() {
+        register(UPLOADER, &[Role::Uploader]);
+        let mut repo = ModelRepository::new();
+        let forged = hex::encode(Sha256::digest(b"forged"));
+
+        // Hash changed after signing: the submitted digest no longer matches
+        let mut stale_digest = upload("m1", &[b"alpha", b"beta"]);
+        stale_digest.manifest.chunks[1].sha256 = forged.clone();
+        let err = repo.submit_model(stale_digest, UPLOADER.to_string()).unwrap_err();
+        assert!(matches!(err.kind, ModelError::VerificationFailed));
+        assert!(err.message.contains("digest mismatch"));
+
+        // Digest recomputed and re-signed: the chunk bytes no longer match
+        let mut resigned = upload("m1", &[b"alpha", b"beta"]);
+        resigned.manifest.chunks[1].sha256 = forged;
+        resigned.signature = Some(sign(&mut resigned.manifest));
+        let err = repo.submit_model(resigned, UPLOADER.to_string()).unwrap_err();
+        assert!(matches!(err.kind, ModelError::VerificationFailed));
+        assert!(err.message.contains("Hash mismatch"));
+
+        assert!(storage_stable::get_manifest("m1").is_err());
+    }
+
+    #[test]
+    fn manifest_chunk_layout_must_tile_without_duplicates_gaps_or_overlaps() {
+        let repo = ModelRepository::new();
+        let manifest = upload("m1", &[b"aaaa", b"bbbb", b"cccc"]).manifest;
+        assert!(repo.validate_manifest(&manifest).is_ok());
+
+        let mut duplicate = manifest.clone();
+        duplicate.chunks[2].id = "chunk-1".to_string();
+        let err = repo.validate_manifest(&duplicate).unwrap_err();
+        assert_eq!(err, "Duplicate chunk id chunk-1");
+
+        let mut gap = manifest.clone();
+        gap.chunks[2].offset = 9;
+        let err = repo.validate_manifest(&gap).unwrap_err();
+        assert!(err.starts_with("Gap before chunk chunk-2"), "{}", err);
+
+        let mut overlap = manifest;
+        overlap.chunks[1].offset = 2;
+        let err = repo.validate_manifest(&overlap).unwrap_err();
+        assert!(err.starts_with("Chunk chunk-1 overlaps"), "{}", err);
+    }
+
+    fn novaq_model() -> NOVAQModel {
+        NOVAQModel {
+            config: NOVAQConfig {
+                target_bits: 4.0,
+                num_subspaces: 2,
+                codebook_size_l1: 4,
+                codebook_size_l2: 4,
+                outlier_threshold: 0.01,
+                teacher_model_path: None,
+                refinement_iterations: 1,
+                kl_weight: 1.0,
+                cosine_weight: 1.0,
+                learning_rate: 0.01,
+                seed: 7,
+            },
+            compression_ratio: 8.0,
+            bit_accuracy: 99.0,
+            vector_codebooks: vec![vec![vec![0.5; 4]; 4]; 2],
+            quantization_indices: vec![vec![0, 1, 2, 3]; 2],
+            weight_shapes: vec![("layer.weight".to_string(), vec![4, 8])],
+            normalization_metadata: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn every_path_honours_the_configured_chunk_limit() {
+        storage_stable::set_max_chunk_size(64).unwrap();
+        let repo = ModelRepository::new();
+        let at_limit = ChunkData { chunk_id: "chunk-0".to_string(), data: vec![1; 64] };
+        let over_limit = ChunkData { chunk_id: "chunk-0".to_string(), data: vec![1; 65] };
+
+        assert!(validation::validate_chunk_integrity(&at_limit).is_ok());
+        assert!(validation::validate_chunk_integrity(&over_limit).is_err());
+
+        assert!(repo.validate_manifest(&upload("m1", &[&at_limit.data]).manifest).is_ok());
+        let err = repo.validate_manifest(&upload("m1", &[&over_limit.data]).manifest).unwrap_err();
+        assert!(err.contains("exceeds the 64 byte limit"), "{}", err);
+
+        assert!(matches!(
+            storage_stable::store_chunk_for_model("m1", "chunk-0", over_limit.data, None),
+            Err(ModelError::StorageFull)
+        ));
+        assert!(storage_stable::store_chunk_for_model("m1", "chunk-0", at_limit.data, None).is_ok());
+
+        // Quantized uploads are split at the same limit
+        let report = NOVAQVerificationReport {
+            bit_accuracy: 99.0,
+            perplexity_delta: None,
+            max_layer_error: None,
+            per_subspace_mse: None,
+        };
+        let quantized = ModelUpload::from_quantized_model("m2".to_string(), "base".to_string(), novaq_model(), report);
+        assert!(quantized.chunks.len() > 1);
+        assert!(quantized.chunks.iter().all(|chunk| validation::validate_chunk_integrity(chunk).is_ok()));
+        assert!(repo.validate_manifest(&quantized.manifest).is_ok());
+    }
+
+    #[test]
+    fn verification_reports_structure_not_the_self_reported_accuracy() {
+        let mut repo = direct_repo();
+        let report = NOVAQVerificationReport {
+            bit_accuracy: 99.0,
+            perplexity_delta: None,
+            max_layer_error: None,
+            per_subspace_mse: None,
+        };
+        let mut quantized = ModelUpload::from_quantized_model("m1".to_string(), "base".to_string(), novaq_model(), report);
+        quantized.signature = Some(sign(&mut quantized.manifest));
+        repo.submit_model(quantized, ADMIN.to_string()).unwrap();
+
+        let structure = repo.verify_model(&ModelId("m1".to_string()), ADMIN.to_string()).unwrap();
+        assert_eq!((structure.subspaces, structure.codebook_entries, structure.indices_checked), (2, 8, 8));
+
+        let record = storage_stable::get_verification("m1").unwrap();
+        assert!(record.passed);
+        assert!(!record.details.contains("accuracy"), "{}", record.details);
+    }
+
+    #[test]
+    fn deprecates_a_family_of_three_models_at_once() {
+        let mut repo = direct_repo();
+        for id in ["llama-7b", "llama-13b", "llama-70b", "llama-next"] {
+            submit(&mut repo, id);
+        }
+        let mut other = upload("mistral-7b", &[b"mistral-7b"]);
+        other.meta = meta("mistral");
+        repo.submit_model(other, ADMIN.to_string()).unwrap();
+        for id in ["llama-7b", "llama-13b", "llama-70b", "mistral-7b"] {
+            repo.activate_model(&ModelId(id.to_string()), None, ADMIN.to_string()).unwrap();
+        }
+
+        let family = storage_stable::query_models_by_family("llama");
+        let mut deprecated: Vec<String> = repo.deprecate_models(&family, ADMIN.to_string()).unwrap()
+            .into_iter().map(|id| id.0).collect();
+        deprecated.sort();
+        assert_eq!(deprecated, vec!["llama-13b", "llama-70b", "llama-7b"]);
+
+        let state = |id: &str| storage_stable::get_manifest(id).unwrap().state;
+        for id in &deprecated {
+            assert!(matches!(state(id), ModelState::Deprecated));
+        }
+        // Pending models and other families are left alone
+        assert!(matches!(state("llama-next"), ModelState::Pending));
+        assert!(matches!(state("mistral-7b"), ModelState::Active));
+
+        let events = storage_stable::query_audit_log(&AuditFilter {
+            event_type: Some(AuditEventType::Deprecate),
+            ..Default::default()
+        });
+        let mut logged: Vec<String> = events.into_iter().map(|e| e.model_id.0).collect();
+        logged.sort();
+        assert_eq!(logged, deprecated);
+    }
+
+    #[test]
+    fn model_counts_track_a_sequence_of_transitions() {
+        let counts = || {
+            let m = crate::infra::metrics::get_metrics();
+            (m.pending_models, m.active_models, m.deprecated_models, m.total_models)
+        };
+        let mut repo = direct_repo();
+        let model = |id: &str| ModelId(id.to_string());
+
+        submit(&mut repo, "m1");
+        submit(&mut repo, "m2");
+        submit(&mut repo, "m3");
+        assert_eq!(counts(), (3, 0, 0, 3));
+
+        repo.activate_model(&model("m1"), None, ADMIN.to_string()).unwrap();
+        repo.activate_model(&model("m2"), None, ADMIN.to_string()).unwrap();
+        assert_eq!(counts(), (1, 2, 0, 3));
+
+        repo.deprecate_model(&model("m1"), ADMIN.to_string()).unwrap();
+        assert_eq!(counts(), (1, 1, 1, 3));
+
+        // A rejected transition leaves the counters alone
+        assert!(repo.delete_model(&model("m3"), ADMIN.to_string()).is_err());
+        assert_eq!(counts(), (1, 1, 1, 3));
+
+        repo.delete_model(&model("m1"), ADMIN.to_string()).unwrap();
+        assert_eq!(counts(), (1, 1, 0, 2));
+
+        // Drift is corrected by recomputing from stable storage
+        crate::infra::metrics::update_model_counts(9, 9, 9);
+        storage_stable::refresh_model_counts();
+        assert_eq!(counts(), (1, 1, 0, 2));
+    }
+
+    #[test]
+    fn submissions_past_the_upload_quota_are_rejected() {
+        register(UPLOADER, &[Role::Uploader]);
+        let mut repo = direct_repo();
+        // Uploaders may not raise their own quota
+        let quota = UploadQuota { max_models: Some(2), max_bytes: Some(8) };
+        let err = repo.set_upload_quota(UPLOADER, Some(quota.clone()), UPLOADER.to_string()).unwrap_err();
+        assert!(matches!(err.kind, ModelError::UnauthorizedAccess));
+        repo.set_upload_quota(UPLOADER, Some(quota), ADMIN.to_string()).unwrap();
+
+        repo.submit_model(upload("m1", &[b"abc"]), UPLOADER.to_string()).unwrap();
+        // 3 + 6 bytes is over the byte limit
+        let err = repo.submit_model(upload("m2", &[b"abcdef"]), UPLOADER.to_string()).unwrap_err();
+        assert!(matches!(err.kind, ModelError::QuotaExceeded));
+        assert!(storage_stable::get_manifest("m2").is_err());
+
+        repo.submit_model(upload("m2", &[b"abcde"]), UPLOADER.to_string()).unwrap();
+        // Two models is the count limit, however small the third is
+        let err = repo.submit_model(upload("m3", &[b"a"]), UPLOADER.to_string()).unwrap_err();
+        assert!(matches!(err.kind, ModelError::QuotaExceeded));
+
+        // Other principals have their own usage
+        submit(&mut repo, "m3");
+
+        // Clearing the override lifts the limit
+        repo.set_upload_quota(UPLOADER, None, ADMIN.to_string()).unwrap();
+        repo.submit_model(upload("m4", &[b"a"]), UPLOADER.to_string()).unwrap();
+    }
+
+    #[test]
+    fn activating_a_model_with_a_missing_chunk_is_rejected() {
+        let mut repo = direct_repo();
+        repo.submit_model(upload("m1", &[b"alpha", b"beta", b"gamma"]), ADMIN.to_string()).unwrap();
+        storage_stable::remove_chunk_for_model("m1", "chunk-1");
+
+        let err = repo.activate_model(&ModelId("m1".to_string()), None, ADMIN.to_string()).unwrap_err();
+        assert!(matches!(err.kind, ModelError::VerificationFailed));
+        assert!(err.message.contains("chunk-1"));
+        assert!(matches!(storage_stable::get_manifest("m1").unwrap().state, ModelState::Pending));
+    }
+
+    /// An active model "m1" that only UPLOADER may read
+    fn private_active_model(repo: &mut ModelRepository) -> ModelId {
+        register(UPLOADER, &[Role::Uploader]);
+        submit(repo, "m1");
+        let model = ModelId("m1".to_string());
+        repo.activate_model(&model, None, ADMIN.to_string()).unwrap();
+        storage_stable::set_access_list("m1", Some(&vec![UPLOADER.to_string()])).unwrap();
+        model
+    }
+
+    fn read_events() -> Vec<AuditEventType> {
+        storage_stable::query_audit_log(&AuditFilter::default()).into_iter()
+            .map(|e| e.event_type)
+            .filter(|t| matches!(t, AuditEventType::ChunkAccess | AuditEventType::ManifestAccess))
+            .collect()
+    }
+
+    #[test]
+    fn reads_of_a_private_model_are_logged_in_audited_mode() {
+        let mut repo = direct_repo();
+        let model = private_active_model(&mut repo);
+        storage_stable::set_audited_reads(true).unwrap();
+
+        // The unlogged query path refuses the private manifest
+        let err = repo.read_manifest(&model).unwrap_err();
+        assert!(matches!(err.kind, ModelError::InvalidState));
+
+        assert_eq!(repo.get_manifest_audited(&model, UPLOADER.to_string()).unwrap().model_id.0, "m1");
+        assert_eq!(repo.get_chunk_audited(&model, "chunk-0", UPLOADER.to_string()).unwrap(), b"m1");
+        assert!(matches!(
+            read_events()[..],
+            [AuditEventType::ManifestAccess, AuditEventType::ChunkAccess]
+        ));
+    }
+
+    #[test]
+    fn reads_are_not_logged_in_normal_mode() {
+        let mut repo = direct_repo();
+        let model = private_active_model(&mut repo);
+
+        assert_eq!(repo.read_manifest(&model).unwrap().model_id.0, "m1");
+        assert_eq!(repo.get_chunk(&model, "chunk-0", UPLOADER).unwrap(), b"m1");
+        assert!(read_events().is_empty());
+    }
+}
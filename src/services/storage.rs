@@ -3,8 +3,13 @@ use ic_stable_structures::{
     DefaultMemoryImpl, StableBTreeMap,
 };
 use std::cell::RefCell;
+use std::ops::Bound;
 use crate::domain::*;
-use candid::{encode_one, decode_one};
+use crate::infra::guards::RateLimitConfig;
+use crate::infra::metrics::{self, Metrics};
+use crate::services::governance::{GovernanceConfig, GovernanceEngine, GovernanceProposal};
+use candid::{encode_one, decode_one, CandidType, Deserialize};
+use sha2::{Digest, Sha256};
 
 type Memory = VirtualMemory<DefaultMemoryImpl>;
 
@@ -25,6 +30,7 @@ thread_local! {
         )
     );
 
+    // Legacy per-model chunk bytes; new writes go to CHUNK_BLOBS
     static CHUNK_STORAGE: RefCell<StableBTreeMap<String, Vec<u8>, Memory>> = RefCell::new(
         StableBTreeMap::init(
             MEMORY_MANAGER.with(|m| m.borrow().get(MemoryId::new(2)))
@@ -36,250 +42,2602 @@ thread_local! {
             MEMORY_MANAGER.with(|m| m.borrow().get(MemoryId::new(3)))
         )
     );
+
+    static MODEL_BENCHMARKS: RefCell<StableBTreeMap<String, Vec<u8>, Memory>> = RefCell::new(
+        StableBTreeMap::init(
+            MEMORY_MANAGER.with(|m| m.borrow().get(MemoryId::new(4)))
+        )
+    );
+
+    static ACCESS_COUNTS: RefCell<StableBTreeMap<String, u64, Memory>> = RefCell::new(
+        StableBTreeMap::init(
+            MEMORY_MANAGER.with(|m| m.borrow().get(MemoryId::new(5)))
+        )
+    );
+
+    static MODEL_SIGNATURES: RefCell<StableBTreeMap<String, Vec<u8>, Memory>> = RefCell::new(
+        StableBTreeMap::init(
+            MEMORY_MANAGER.with(|m| m.borrow().get(MemoryId::new(6)))
+        )
+    );
+
+    static UPLOADER_KEYS: RefCell<StableBTreeMap<String, Vec<u8>, Memory>> = RefCell::new(
+        StableBTreeMap::init(
+            MEMORY_MANAGER.with(|m| m.borrow().get(MemoryId::new(7)))
+        )
+    );
+
+    static AUDIT_EVENTS: RefCell<StableBTreeMap<u64, Vec<u8>, Memory>> = RefCell::new(
+        StableBTreeMap::init(
+            MEMORY_MANAGER.with(|m| m.borrow().get(MemoryId::new(8)))
+        )
+    );
+
+    static MODEL_BADGES: RefCell<StableBTreeMap<String, Vec<u8>, Memory>> = RefCell::new(
+        StableBTreeMap::init(
+            MEMORY_MANAGER.with(|m| m.borrow().get(MemoryId::new(9)))
+        )
+    );
+
+    static UPLOAD_SESSIONS: RefCell<StableBTreeMap<String, Vec<u8>, Memory>> = RefCell::new(
+        StableBTreeMap::init(
+            MEMORY_MANAGER.with(|m| m.borrow().get(MemoryId::new(10)))
+        )
+    );
+
+    static STAGED_CHUNKS: RefCell<StableBTreeMap<String, Vec<u8>, Memory>> = RefCell::new(
+        StableBTreeMap::init(
+            MEMORY_MANAGER.with(|m| m.borrow().get(MemoryId::new(11)))
+        )
+    );
+
+    static PRINCIPAL_ROLES: RefCell<StableBTreeMap<String, Vec<u8>, Memory>> = RefCell::new(
+        StableBTreeMap::init(
+            MEMORY_MANAGER.with(|m| m.borrow().get(MemoryId::new(12)))
+        )
+    );
+
+    static MODEL_VERIFICATIONS: RefCell<StableBTreeMap<String, Vec<u8>, Memory>> = RefCell::new(
+        StableBTreeMap::init(
+            MEMORY_MANAGER.with(|m| m.borrow().get(MemoryId::new(13)))
+        )
+    );
+
+    // Content-addressed chunk bytes, keyed by sha256 hex
+    static CHUNK_BLOBS: RefCell<StableBTreeMap<String, Vec<u8>, Memory>> = RefCell::new(
+        StableBTreeMap::init(
+            MEMORY_MANAGER.with(|m| m.borrow().get(MemoryId::new(14)))
+        )
+    );
+
+    static CHUNK_REFCOUNTS: RefCell<StableBTreeMap<String, u64, Memory>> = RefCell::new(
+        StableBTreeMap::init(
+            MEMORY_MANAGER.with(|m| m.borrow().get(MemoryId::new(15)))
+        )
+    );
+
+    // model_id:chunk_id -> sha256 hex of the chunk's bytes in CHUNK_BLOBS
+    static CHUNK_INDEX: RefCell<StableBTreeMap<String, String, Memory>> = RefCell::new(
+        StableBTreeMap::init(
+            MEMORY_MANAGER.with(|m| m.borrow().get(MemoryId::new(16)))
+        )
+    );
+
+    // "family:<name>" / "arch:<name>" / "tokenizer:<id>" / "badge:<type>" / "tag:<tag>"
+    // -> encoded Vec<String> of model ids
+    static META_INDEX: RefCell<StableBTreeMap<String, Vec<u8>, Memory>> = RefCell::new(
+        StableBTreeMap::init(
+            MEMORY_MANAGER.with(|m| m.borrow().get(MemoryId::new(17)))
+        )
+    );
+
+    static GOVERNANCE_PROPOSALS: RefCell<StableBTreeMap<u64, Vec<u8>, Memory>> = RefCell::new(
+        StableBTreeMap::init(
+            MEMORY_MANAGER.with(|m| m.borrow().get(MemoryId::new(18)))
+        )
+    );
+
+    // "<caller>:<token>" -> IdempotencyRecord of a completed submission
+    static IDEMPOTENCY_KEYS: RefCell<StableBTreeMap<String, Vec<u8>, Memory>> = RefCell::new(
+        StableBTreeMap::init(
+            MEMORY_MANAGER.with(|m| m.borrow().get(MemoryId::new(19)))
+        )
+    );
+
+    // Latest reproducible ReproducibilityReport per model
+    static MODEL_REPRODUCIBILITY: RefCell<StableBTreeMap<String, Vec<u8>, Memory>> = RefCell::new(
+        StableBTreeMap::init(
+            MEMORY_MANAGER.with(|m| m.borrow().get(MemoryId::new(20)))
+        )
+    );
+
+    // model_id -> encoded Vec<String> of normalized tags
+    static MODEL_TAGS: RefCell<StableBTreeMap<String, Vec<u8>, Memory>> = RefCell::new(
+        StableBTreeMap::init(
+            MEMORY_MANAGER.with(|m| m.borrow().get(MemoryId::new(21)))
+        )
+    );
+
+    // sha256 hex -> encoded ChunkCodec for blobs not stored raw
+    static CHUNK_CODECS: RefCell<StableBTreeMap<String, Vec<u8>, Memory>> = RefCell::new(
+        StableBTreeMap::init(
+            MEMORY_MANAGER.with(|m| m.borrow().get(MemoryId::new(22)))
+        )
+    );
+
+    // model_id -> encoded Vec<String> of principals allowed to read chunks;
+    // models without an entry are public
+    static MODEL_ACCESS_LISTS: RefCell<StableBTreeMap<String, Vec<u8>, Memory>> = RefCell::new(
+        StableBTreeMap::init(
+            MEMORY_MANAGER.with(|m| m.borrow().get(MemoryId::new(23)))
+        )
+    );
+
+    // model_id -> encoded NOVAQVerificationReport attested by the uploader
+    static MODEL_QUALITY_REPORTS: RefCell<StableBTreeMap<String, Vec<u8>, Memory>> = RefCell::new(
+        StableBTreeMap::init(
+            MEMORY_MANAGER.with(|m| m.borrow().get(MemoryId::new(24)))
+        )
+    );
+
+    // principal -> encoded UploadQuota overriding the default quota
+    static UPLOAD_QUOTAS: RefCell<StableBTreeMap<String, Vec<u8>, Memory>> = RefCell::new(
+        StableBTreeMap::init(
+            MEMORY_MANAGER.with(|m| m.borrow().get(MemoryId::new(25)))
+        )
+    );
 }
 
 fn chunk_key(model_id: &str, chunk_id: &str) -> String {
     format!("{}:{}", model_id, chunk_id)
 }
 
-const AUTH_UPLOADERS_KEY: &str = "__auth_uploaders";
-const AUDIT_LOG_KEY: &str = "__audit_log";
+const AUTH_UPLOADERS_KEY: &str = "__auth_uploaders"; // Legacy flat uploader list
+const AUDIT_LOG_KEY: &str = "__audit_log"; // Legacy single-blob layout
+const AUDIT_NEXT_INDEX_KEY: &str = "__audit_next_index";
+const ACTIVATION_GATES_KEY: &str = "__activation_gates";
+const AUDITED_READS_KEY: &str = "__audited_reads";
+const INTEGRITY_SWEEP_KEY: &str = "__integrity_sweep";
+const GOVERNANCE_KEY: &str = "__governance"; // Legacy pre_upgrade snapshot
+const GOVERNANCE_NEXT_ID_KEY: &str = "__governance_next_id";
+const GOVERNANCE_CONFIG_KEY: &str = "__governance_config";
+const METRICS_KEY: &str = "__metrics";
+const UPLOAD_SESSION_SEQ_KEY: &str = "__upload_session_seq";
+const STORED_BYTES_KEY: &str = "__stored_bytes";
+const RATE_LIMITS_KEY: &str = "__rate_limits";
+const MAINTENANCE_KEY: &str = "__maintenance";
+const MAINTENANCE_INTERVAL_KEY: &str = "__maintenance_interval";
+const GOVERNANCE_ENABLED_KEY: &str = "__governance_enabled";
+const MAX_CHUNK_SIZE_KEY: &str = "__max_chunk_size";
+const ENERGY_PER_GB_KEY: &str = "__energy_per_gb";
+const SCHEMA_VERSION_KEY: &str = "__schema_version";
+const AUDIT_SAMPLING_KEY: &str = "__audit_sampling";
+const MIN_COMPRESSION_RATIO_KEY: &str = "__min_compression_ratio";
+const HIGH_COMPRESSION_THRESHOLD_KEY: &str = "__high_compression_threshold";
+const DEFAULT_UPLOAD_QUOTA_KEY: &str = "__default_upload_quota";
+const DEPRECATION_RETENTION_KEY: &str = "__deprecation_retention";
+
+/// Layout version this build reads and writes. Bump it with every change
+/// that needs existing stable data migrated.
+/// 2: manifests, metadata and audit events are wrapped in `StoredRecord`
+/// 3: manifests record their uploader
+pub const SCHEMA_VERSION: u32 = 3;
+
+/// Version of the manifest, metadata and audit event shapes written by this
+/// build. Bump it when one of those types changes and teach `upgrade_record`
+/// how to read the previous shape.
+pub const RECORD_VERSION: u16 = 1;
+
+/// Envelope around versioned records. Records written before the envelope
+/// are bare candid values and read as version 0.
+#[derive(CandidType, Deserialize)]
+struct StoredRecord {
+    record_version: u16,
+    payload: Vec<u8>,
+}
+
+fn encode_record<T: CandidType>(value: &T) -> ModelResult<Vec<u8>> {
+    let payload = encode_one(value).map_err(|_| ModelError::InvalidFormat)?;
+    encode_one(StoredRecord { record_version: RECORD_VERSION, payload })
+        .map_err(|_| ModelError::InvalidFormat)
+}
+
+/// Split stored bytes into their record version and candid payload
+fn open_record(data: &[u8]) -> (u16, Vec<u8>) {
+    match decode_one::<StoredRecord>(data) {
+        Ok(record) => (record.record_version, record.payload),
+        Err(_) => (0, data.to_vec()),
+    }
+}
+
+fn decode_record<T>(data: &[u8]) -> ModelResult<T>
+where
+    T: CandidType + for<'de> Deserialize<'de>,
+{
+    let (version, payload) = open_record(data);
+    upgrade_record(version, payload)
+}
+
+/// Read a payload written with record version `version` as the current
+/// shape. Version 0 and 1 share a layout; later versions add arms here that
+/// decode the old shape and convert it.
+fn upgrade_record<T>(version: u16, payload: Vec<u8>) -> ModelResult<T>
+where
+    T: CandidType + for<'de> Deserialize<'de>,
+{
+    match version {
+        0 | RECORD_VERSION => decode_one(&payload).map_err(|_| ModelError::InvalidFormat),
+        _ => Err(ModelError::InvalidFormat),
+    }
+}
+
+/// Rewrite records stored under an older record version, or without the
+/// envelope, in the current layout. Records that cannot be read are left
+/// untouched so they are not lost.
+pub fn migrate_storage() -> MigrationReport {
+    let mut report = MigrationReport::default();
+    migrate_map::<String, ModelManifest>(&MODEL_MANIFESTS, &mut report);
+    migrate_map::<String, ModelMeta>(&MODEL_METADATA, &mut report);
+    migrate_map::<u64, AuditEvent>(&AUDIT_EVENTS, &mut report);
+    report.migrated += backfill_uploaders();
+    report
+}
+
+/// Fill in the uploader of manifests written before it was recorded, from
+/// the model's latest Upload audit event. Returns how many were filled.
+fn backfill_uploaders() -> u64 {
+    let mut owners: std::collections::HashMap<String, String> = std::collections::HashMap::new();
+    AUDIT_EVENTS.with(|storage| {
+        for (_, data) in storage.borrow().iter() {
+            if let Ok(event) = decode_record::<AuditEvent>(&data) {
+                if event.event_type == AuditEventType::Upload {
+                    owners.insert(event.model_id.0, event.actor);
+                }
+            }
+        }
+    });
+
+    let mut filled = 0u64;
+    for model_id in list_models() {
+        let Ok(mut manifest) = get_manifest(&model_id) else {
+            continue;
+        };
+        if manifest.uploader.is_some() {
+            continue;
+        }
+        if let Some(owner) = owners.remove(&model_id) {
+            manifest.uploader = Some(owner);
+            if store_manifest(&model_id, &manifest).is_ok() {
+                filled += 1;
+            }
+        }
+    }
+    filled
+}
+
+fn migrate_map<K, T>(
+    map: &'static std::thread::LocalKey<RefCell<StableBTreeMap<K, Vec<u8>, Memory>>>,
+    report: &mut MigrationReport,
+) where
+    K: ic_stable_structures::Storable + Ord + Clone,
+    T: CandidType + for<'de> Deserialize<'de>,
+{
+    let entries: Vec<(K, Vec<u8>)> = map.with(|storage| storage.borrow().iter().collect());
+    for (key, data) in entries {
+        let (version, payload) = open_record(&data);
+        if version == RECORD_VERSION {
+            continue;
+        }
+        let migrated = upgrade_record::<T>(version, payload).and_then(|value| encode_record(&value));
+        match migrated {
+            Ok(data) => {
+                map.with(|storage| storage.borrow_mut().insert(key, data));
+                report.migrated += 1;
+            }
+            Err(_) => report.failed += 1,
+        }
+    }
+}
+
+/// Outcome of a `migrate_storage` pass
+#[derive(Debug, Default)]
+pub struct MigrationReport {
+    pub migrated: u64,
+    pub failed: u64,
+}
+
+/// Energy attributed to each GB of model size saved, in kWh, until an
+/// admin sets a different coefficient
+pub const DEFAULT_ENERGY_PER_GB_KWH: f32 = 71.0;
+
+/// Submitted NOVAQ models must compress by strictly more than this ratio
+pub const DEFAULT_MIN_COMPRESSION_RATIO: f32 = 1.0;
+/// Ratio a model needs before it may hold the HighCompression badge
+pub const DEFAULT_HIGH_COMPRESSION_THRESHOLD: f32 = 10.0;
+
+/// Largest chunk the limit can be raised to, and its default: a chunk has
+/// to fit in a single IC message
+pub const MAX_CHUNK_SIZE: u64 = 2 * 1024 * 1024;
+const STATS_TOTALS_KEY: &str = "__stats_totals";
+const STORAGE_CAPACITY_KEY: &str = "__storage_capacity";
+
+// Model manifest storage
+pub fn store_manifest(model_id: &str, manifest: &ModelManifest) -> ModelResult<()> {
+    let manifest_data = encode_record(manifest)?;
+    
+    // Swap the replaced manifest's contribution to the stats for this one's
+    let mut stats = get_stats_totals();
+    let previous = get_manifest(model_id).ok();
+    if let Some(previous) = &previous {
+        stats.remove(previous);
+    }
+    stats.add(manifest);
+    
+    MODEL_MANIFESTS.with(|storage| {
+        storage.borrow_mut().insert(model_id.to_string(), manifest_data);
+    });
+    metrics::record_state_change(previous.as_ref().map(|m| &m.state), Some(&manifest.state));
+    
+    set_stats_totals(&stats)
+}
+
+pub fn get_manifest(model_id: &str) -> ModelResult<ModelManifest> {
+    MODEL_MANIFESTS.with(|storage| {
+        storage.borrow().get(&model_id.to_string())
+            .ok_or(ModelError::NotFound)
+            .and_then(|data| decode_record(&data))
+    })
+}
+
+// Model metadata storage
+pub fn store_model_meta(model_id: &str, meta: &ModelMeta) -> ModelResult<()> {
+    let meta_data = encode_record(meta)?;
+    
+    // Re-point the metadata index if this replaces existing meta
+    if let Ok(previous) = get_model_meta(model_id) {
+        unindex_model_meta(model_id, &previous)?;
+    }
+    
+    MODEL_METADATA.with(|storage| {
+        storage.borrow_mut().insert(model_id.to_string(), meta_data);
+    });
+    
+    index_model_meta(model_id, meta)
+}
+
+fn family_index_key(family: &str) -> String {
+    format!("family:{}", family.to_lowercase())
+}
+
+fn arch_index_key(arch: &str) -> String {
+    format!("arch:{}", arch.to_lowercase())
+}
+
+fn tokenizer_index_key(tokenizer_id: &str) -> String {
+    format!("tokenizer:{}", tokenizer_id.to_lowercase())
+}
+
+fn badge_index_key(badge_type: &BadgeType) -> String {
+    format!("badge:{:?}", badge_type)
+}
+
+fn tag_index_key(tag: &str) -> String {
+    format!("tag:{}", tag)
+}
+
+/// Every META_INDEX key a model's metadata is listed under
+fn meta_index_keys(meta: &ModelMeta) -> [String; 3] {
+    [
+        family_index_key(&meta.family),
+        arch_index_key(&meta.arch),
+        tokenizer_index_key(&meta.tokenizer_id),
+    ]
+}
+
+fn get_index_entry(key: &str) -> Vec<String> {
+    META_INDEX.with(|index| {
+        index
+            .borrow()
+            .get(&key.to_string())
+            .and_then(|data| decode_one::<Vec<String>>(&data).ok())
+            .unwrap_or_default()
+    })
+}
+
+fn set_index_entry(key: &str, model_ids: &Vec<String>) -> ModelResult<()> {
+    META_INDEX.with(|index| {
+        let mut index = index.borrow_mut();
+        if model_ids.is_empty() {
+            index.remove(&key.to_string());
+        } else {
+            let data = encode_one(model_ids).map_err(|_| ModelError::InvalidFormat)?;
+            index.insert(key.to_string(), data);
+        }
+        Ok(())
+    })
+}
+
+fn add_to_index(key: &str, model_id: &str) -> ModelResult<()> {
+    let mut ids = get_index_entry(key);
+    if !ids.iter().any(|id| id == model_id) {
+        ids.push(model_id.to_string());
+        set_index_entry(key, &ids)?;
+    }
+    Ok(())
+}
+
+fn remove_from_index(key: &str, model_id: &str) -> ModelResult<()> {
+    let mut ids = get_index_entry(key);
+    ids.retain(|id| id != model_id);
+    set_index_entry(key, &ids)
+}
+
+fn index_model_meta(model_id: &str, meta: &ModelMeta) -> ModelResult<()> {
+    for key in meta_index_keys(meta) {
+        add_to_index(&key, model_id)?;
+    }
+    Ok(())
+}
+
+fn unindex_model_meta(model_id: &str, meta: &ModelMeta) -> ModelResult<()> {
+    for key in meta_index_keys(meta) {
+        remove_from_index(&key, model_id)?;
+    }
+    Ok(())
+}
+
+/// Strip embedded NOVAQ models from stored manifests, keeping only their
+/// config and compression figures. Returns how many manifests changed.
+pub fn strip_embedded_models() -> ModelResult<u64> {
+    let mut stripped = 0u64;
+    for model_id in list_models() {
+        let Ok(mut manifest) = get_manifest(&model_id) else {
+            continue;
+        };
+        if manifest.strip_embedded_model() {
+            store_manifest(&model_id, &manifest)?;
+            stripped += 1;
+        }
+    }
+    Ok(stripped)
+}
+
+/// Rebuild the metadata, tag and badge index from their source maps, e.g. after an
+/// upgrade from a version that did not maintain it
+pub fn rebuild_meta_index() -> ModelResult<()> {
+    META_INDEX.with(|index| {
+        let mut index = index.borrow_mut();
+        let keys: Vec<String> = index.iter().map(|(k, _)| k).collect();
+        for key in keys {
+            index.remove(&key);
+        }
+    });
+    let entries: Vec<(String, Vec<u8>)> = MODEL_METADATA.with(|storage| storage.borrow().iter().collect());
+    for (model_id, data) in entries {
+        if let Ok(meta) = decode_record::<ModelMeta>(&data) {
+            index_model_meta(&model_id, &meta)?;
+        }
+    }
+    let tag_entries: Vec<(String, Vec<u8>)> = MODEL_TAGS.with(|storage| storage.borrow().iter().collect());
+    for (model_id, data) in tag_entries {
+        for tag in decode_one::<Vec<String>>(&data).unwrap_or_default() {
+            add_to_index(&tag_index_key(&tag), &model_id)?;
+        }
+    }
+    let badge_entries: Vec<(String, Vec<u8>)> = MODEL_BADGES.with(|storage| storage.borrow().iter().collect());
+    for (model_id, data) in badge_entries {
+        for badge in decode_one::<Vec<Badge>>(&data).unwrap_or_default() {
+            add_to_index(&badge_index_key(&badge.badge_type), &model_id)?;
+        }
+    }
+    Ok(())
+}
+
+pub fn query_models_by_family(family: &str) -> Vec<ModelId> {
+    get_index_entry(&family_index_key(family)).into_iter().map(ModelId).collect()
+}
+
+/// Manifests submitted by `principal`, in model id order
+pub fn list_models_by_uploader(principal: &str) -> Vec<ModelManifest> {
+    MODEL_MANIFESTS.with(|storage| {
+        storage
+            .borrow()
+            .iter()
+            .filter_map(|(_, manifest_data)| decode_record::<ModelManifest>(&manifest_data).ok())
+            .filter(|manifest| manifest.uploader.as_deref() == Some(principal))
+            .collect()
+    })
+}
+
+/// The Active model of `family`; if several are Active, the most recently
+/// activated one, which is the head of its version chain
+pub fn get_active_model_for_family(family: &str) -> Option<ModelManifest> {
+    query_models_by_family(family)
+        .iter()
+        .filter_map(|model_id| get_manifest(&model_id.0).ok())
+        .filter(|manifest| matches!(manifest.state, ModelState::Active))
+        .max_by_key(|manifest| manifest.activated_at.unwrap_or(0))
+}
+
+pub fn query_models_by_arch(arch: &str) -> Vec<ModelId> {
+    get_index_entry(&arch_index_key(arch)).into_iter().map(ModelId).collect()
+}
+
+pub fn query_models_by_tokenizer(tokenizer_id: &str) -> Vec<ModelId> {
+    get_index_entry(&tokenizer_index_key(tokenizer_id)).into_iter().map(ModelId).collect()
+}
+
+pub fn query_models_by_badge(badge_type: &BadgeType) -> Vec<ModelId> {
+    get_index_entry(&badge_index_key(badge_type)).into_iter().map(ModelId).collect()
+}
+
+/// Lowercased, trimmed form tags are stored and looked up under
+pub fn normalize_tag(tag: &str) -> String {
+    tag.trim().to_lowercase()
+}
+
+pub fn query_models_by_tag(tag: &str) -> Vec<ModelId> {
+    get_index_entry(&tag_index_key(&normalize_tag(tag))).into_iter().map(ModelId).collect()
+}
+
+pub fn get_model_tags(model_id: &str) -> Vec<String> {
+    MODEL_TAGS.with(|storage| {
+        storage
+            .borrow()
+            .get(&model_id.to_string())
+            .and_then(|data| decode_one::<Vec<String>>(&data).ok())
+            .unwrap_or_default()
+    })
+}
+
+pub fn get_access_list(model_id: &str) -> Option<Vec<String>> {
+    MODEL_ACCESS_LISTS.with(|storage| {
+        storage
+            .borrow()
+            .get(&model_id.to_string())
+            .and_then(|data| decode_one::<Vec<String>>(&data).ok())
+    })
+}
+
+/// Restrict chunk reads to `access_list`, or make the model public with `None`
+pub fn set_access_list(model_id: &str, access_list: Option<&Vec<String>>) -> ModelResult<()> {
+    MODEL_ACCESS_LISTS.with(|storage| {
+        let mut storage = storage.borrow_mut();
+        match access_list {
+            Some(principals) => {
+                let data = encode_one(principals).map_err(|_| ModelError::InvalidFormat)?;
+                storage.insert(model_id.to_string(), data);
+            }
+            None => {
+                storage.remove(&model_id.to_string());
+            }
+        }
+        Ok(())
+    })
+}
+
+/// Replace a model's tags (already normalized), keeping the tag index in step
+pub fn set_model_tags(model_id: &str, tags: &Vec<String>) -> ModelResult<()> {
+    for previous in get_model_tags(model_id) {
+        if !tags.contains(&previous) {
+            remove_from_index(&tag_index_key(&previous), model_id)?;
+        }
+    }
+    for tag in tags {
+        add_to_index(&tag_index_key(tag), model_id)?;
+    }
+
+    MODEL_TAGS.with(|storage| {
+        let mut storage = storage.borrow_mut();
+        if tags.is_empty() {
+            storage.remove(&model_id.to_string());
+        } else {
+            let data = encode_one(tags).map_err(|_| ModelError::InvalidFormat)?;
+            storage.insert(model_id.to_string(), data);
+        }
+        Ok(())
+    })
+}
+
+pub fn get_model_meta(model_id: &str) -> ModelResult<ModelMeta> {
+    MODEL_METADATA.with(|storage| {
+        storage.borrow().get(&model_id.to_string())
+            .ok_or(ModelError::NotFound)
+            .and_then(|data| decode_record(&data))
+    })
+}
+
+// Chunk storage: bytes are content-addressed and shared between models;
+// each model:chunk key points at a hash and holds one reference to it
+/// Store a chunk, compressing it with `codec` when that saves space.
+/// Returns the codec actually applied and the bytes held in stable memory.
+pub fn store_chunk_for_model(
+    model_id: &str,
+    chunk_id: &str,
+    chunk_data: Vec<u8>,
+    codec: Option<ChunkCodec>,
+) -> ModelResult<(Option<ChunkCodec>, u64)> {
+    // Validate chunk size
+    if chunk_data.len() as u64 > get_max_chunk_size() {
+        return Err(ModelError::StorageFull);
+    }
+    
+    let key = chunk_key(model_id, chunk_id);
+    let hash = hex::encode(Sha256::digest(&chunk_data));
+    let previous = CHUNK_INDEX.with(|index| index.borrow().get(&key));
+    if previous.as_deref() == Some(hash.as_str()) {
+        return Ok(blob_info(&hash));
+    }
+
+    // Only bytes not already held under this hash are encoded and count
+    // against capacity
+    let is_new_blob = !CHUNK_BLOBS.with(|blobs| blobs.borrow().contains_key(&hash));
+    let (codec, chunk_data) = if is_new_blob {
+        encode_chunk(chunk_data, codec)
+    } else {
+        (None, chunk_data)
+    };
+    if is_new_blob {
+        if let Some(capacity) = get_storage_capacity() {
+            // Credit what this write frees: the key's old blob when nothing
+            // else references it, and any legacy copy
+            let released_blob = previous.as_deref()
+                .filter(|hash| CHUNK_REFCOUNTS.with(|counts| counts.borrow().get(&hash.to_string())).unwrap_or(0) <= 1)
+                .map_or(0, |hash| blob_info(hash).1);
+            let released_legacy = CHUNK_STORAGE.with(|storage| storage.borrow().get(&key))
+                .map_or(0, |data| data.len() as u64);
+            let in_use = get_stored_bytes().saturating_sub(released_blob + released_legacy);
+            if in_use.saturating_add(chunk_data.len() as u64) > capacity {
+                return Err(ModelError::StorageFull);
+            }
+        }
+    }
+    
+    let stored = acquire_blob(&hash, chunk_data, codec);
+    CHUNK_INDEX.with(|index| {
+        index.borrow_mut().insert(key.clone(), hash);
+    });
+    if let Some(previous) = previous {
+        release_blob(&previous);
+    }
+    // Drop any legacy copy so reads cannot see stale bytes
+    remove_legacy_chunk(&key);
+    
+    Ok(stored)
+}
+
+/// Apply `codec`, keeping the raw bytes when it does not shrink them
+fn encode_chunk(data: Vec<u8>, codec: Option<ChunkCodec>) -> (Option<ChunkCodec>, Vec<u8>) {
+    match codec {
+        Some(ChunkCodec::Lz4) => {
+            let compressed = lz4_flex::compress_prepend_size(&data);
+            if compressed.len() < data.len() {
+                (Some(ChunkCodec::Lz4), compressed)
+            } else {
+                (None, data)
+            }
+        }
+        None => (None, data),
+    }
+}
+
+fn decode_chunk(data: Vec<u8>, codec: Option<ChunkCodec>) -> ModelResult<Vec<u8>> {
+    match codec {
+        Some(ChunkCodec::Lz4) => lz4_flex::decompress_size_prepended(&data)
+            .map_err(|_| ModelError::CompressionFailed),
+        None => Ok(data),
+    }
+}
+
+fn get_blob_codec(hash: &str) -> Option<ChunkCodec> {
+    CHUNK_CODECS.with(|codecs| codecs.borrow().get(&hash.to_string()))
+        .and_then(|data| decode_one::<ChunkCodec>(&data).ok())
+}
+
+fn set_blob_codec(hash: &str, codec: Option<ChunkCodec>) {
+    CHUNK_CODECS.with(|codecs| {
+        let mut codecs = codecs.borrow_mut();
+        match codec.and_then(|codec| encode_one(codec).ok()) {
+            Some(data) => codecs.insert(hash.to_string(), data),
+            None => codecs.remove(&hash.to_string()),
+        };
+    });
+}
+
+/// Codec and stored size of the blob held under `hash`
+fn blob_info(hash: &str) -> (Option<ChunkCodec>, u64) {
+    let size = CHUNK_BLOBS.with(|blobs| blobs.borrow().get(&hash.to_string()))
+        .map(|data| data.len() as u64)
+        .unwrap_or(0);
+    (get_blob_codec(hash), size)
+}
+
+fn remove_legacy_chunk(key: &String) {
+    // Read the total before mutating so a first-use measurement is exact
+    let stored = get_stored_bytes();
+    if let Some(data) = CHUNK_STORAGE.with(|storage| storage.borrow_mut().remove(key)) {
+        set_stored_bytes(stored.saturating_sub(data.len() as u64));
+    }
+}
+
+pub fn remove_chunk_for_model(model_id: &str, chunk_id: &str) {
+    let key = chunk_key(model_id, chunk_id);
+    if let Some(hash) = CHUNK_INDEX.with(|index| index.borrow_mut().remove(&key)) {
+        release_blob(&hash);
+    }
+    remove_legacy_chunk(&key);
+}
+
+/// Prefix of the namespace an overwrite's chunks are staged under; model
+/// ids may not start with it
+pub const STAGING_PREFIX: &str = "__staged:";
+
+/// Namespace an overwrite's chunks are written under until all of them are stored
+pub fn staging_model_id(model_id: &str) -> String {
+    format!("{}{}", STAGING_PREFIX, model_id)
+}
+
+/// Move a staged chunk onto the model's key, releasing the blob it replaces
+pub fn promote_staged_chunk(model_id: &str, chunk_id: &str) {
+    let staged = chunk_key(&staging_model_id(model_id), chunk_id);
+    let Some(hash) = CHUNK_INDEX.with(|index| index.borrow_mut().remove(&staged)) else {
+        return;
+    };
+    let key = chunk_key(model_id, chunk_id);
+    if let Some(previous) = CHUNK_INDEX.with(|index| index.borrow_mut().insert(key.clone(), hash)) {
+        release_blob(&previous);
+    }
+    remove_legacy_chunk(&key);
+}
+
+/// Whether the chunk's bytes are stored, without reading or decoding them
+pub fn has_chunk_for_model(model_id: &str, chunk_id: &str) -> bool {
+    let key = chunk_key(model_id, chunk_id);
+    if let Some(hash) = CHUNK_INDEX.with(|index| index.borrow().get(&key)) {
+        return CHUNK_BLOBS.with(|blobs| blobs.borrow().contains_key(&hash));
+    }
+    CHUNK_STORAGE.with(|storage| storage.borrow().contains_key(&key))
+}
+
+pub fn get_chunk_for_model(model_id: &str, chunk_id: &str) -> ModelResult<Vec<u8>> {
+    let key = chunk_key(model_id, chunk_id);
+    if let Some(hash) = CHUNK_INDEX.with(|index| index.borrow().get(&key)) {
+        let data = CHUNK_BLOBS.with(|blobs| blobs.borrow().get(&hash))
+            .ok_or(ModelError::NotFound)?;
+        return decode_chunk(data, get_blob_codec(&hash));
+    }
+    CHUNK_STORAGE.with(|storage| {
+        storage.borrow().get(&key)
+            .ok_or(ModelError::NotFound)
+    })
+}
+
+/// Add a reference to `hash`, storing the (encoded) bytes only if they are
+/// new. Returns the codec and size of the blob now held.
+fn acquire_blob(hash: &str, data: Vec<u8>, codec: Option<ChunkCodec>) -> (Option<ChunkCodec>, u64) {
+    let refs = CHUNK_REFCOUNTS.with(|counts| counts.borrow().get(&hash.to_string())).unwrap_or(0);
+    let info = if refs == 0 {
+        let stored = get_stored_bytes();
+        let size = data.len() as u64;
+        let replaced = CHUNK_BLOBS.with(|blobs| blobs.borrow_mut().insert(hash.to_string(), data));
+        if replaced.is_none() {
+            set_stored_bytes(stored.saturating_add(size));
+        }
+        set_blob_codec(hash, codec);
+        (codec, size)
+    } else {
+        blob_info(hash)
+    };
+    CHUNK_REFCOUNTS.with(|counts| {
+        counts.borrow_mut().insert(hash.to_string(), refs + 1);
+    });
+    info
+}
+
+/// Drop a reference to `hash`, deleting the bytes when none remain
+fn release_blob(hash: &str) {
+    let refs = CHUNK_REFCOUNTS.with(|counts| counts.borrow().get(&hash.to_string())).unwrap_or(0);
+    if refs <= 1 {
+        let stored = get_stored_bytes();
+        CHUNK_REFCOUNTS.with(|counts| {
+            counts.borrow_mut().remove(&hash.to_string());
+        });
+        if let Some(data) = CHUNK_BLOBS.with(|blobs| blobs.borrow_mut().remove(&hash.to_string())) {
+            set_stored_bytes(stored.saturating_sub(data.len() as u64));
+        }
+        set_blob_codec(hash, None);
+    } else {
+        CHUNK_REFCOUNTS.with(|counts| {
+            counts.borrow_mut().insert(hash.to_string(), refs - 1);
+        });
+    }
+}
+
+/// References held on the blob stored under `hash`
+#[cfg(test)]
+pub(crate) fn blob_refcount(hash: &str) -> u64 {
+    CHUNK_REFCOUNTS.with(|counts| counts.borrow().get(&hash.to_string())).unwrap_or(0)
+}
+
+// Running total of stored chunk bytes, shared blobs counted once. Trees
+// from before the counter existed are measured on first use.
+pub fn get_stored_bytes() -> u64 {
+    let stored = MODEL_STATS.with(|storage| {
+        storage
+            .borrow()
+            .get(&STORED_BYTES_KEY.to_string())
+            .and_then(|data| decode_one::<u64>(&data).ok())
+    });
+    stored.unwrap_or_else(|| {
+        let blob_bytes: u64 = CHUNK_BLOBS.with(|blobs| {
+            blobs.borrow().iter().map(|(_, data)| data.len() as u64).sum()
+        });
+        let legacy_bytes: u64 = CHUNK_STORAGE.with(|storage| {
+            storage.borrow().iter().map(|(_, data)| data.len() as u64).sum()
+        });
+        blob_bytes + legacy_bytes
+    })
+}
+
+fn set_stored_bytes(bytes: u64) {
+    if let Ok(data) = encode_one(bytes) {
+        MODEL_STATS.with(|storage| {
+            storage.borrow_mut().insert(STORED_BYTES_KEY.to_string(), data);
+        });
+    }
+}
+
+// Soft capacity for chunk bytes; `None` means unlimited
+pub fn get_storage_capacity() -> Option<u64> {
+    MODEL_STATS.with(|storage| {
+        storage
+            .borrow()
+            .get(&STORAGE_CAPACITY_KEY.to_string())
+            .and_then(|data| decode_one::<u64>(&data).ok())
+    })
+}
+
+pub fn set_storage_capacity(capacity: Option<u64>) -> ModelResult<()> {
+    match capacity {
+        Some(bytes) => {
+            let data = encode_one(bytes).map_err(|_| ModelError::InvalidFormat)?;
+            MODEL_STATS.with(|storage| {
+                storage.borrow_mut().insert(STORAGE_CAPACITY_KEY.to_string(), data);
+            });
+        }
+        None => {
+            MODEL_STATS.with(|storage| {
+                storage.borrow_mut().remove(&STORAGE_CAPACITY_KEY.to_string());
+            });
+        }
+    }
+    Ok(())
+}
+
+// Model listing and queries
+pub fn list_models() -> Vec<String> {
+    MODEL_MANIFESTS.with(|storage| {
+        storage.borrow().iter().map(|(k, _)| k).collect()
+    })
+}
+
+/// Model ids in key order strictly after `cursor`, for incremental scans
+pub fn list_models_after(cursor: Option<&str>, limit: usize) -> Vec<String> {
+    let start = match cursor {
+        Some(c) => Bound::Excluded(c.to_string()),
+        None => Bound::Unbounded,
+    };
+    MODEL_MANIFESTS.with(|storage| {
+        storage
+            .borrow()
+            .range((start, Bound::Unbounded))
+            .take(limit)
+            .map(|(k, _)| k)
+            .collect()
+    })
+}
+
+pub fn list_quantized_models() -> Vec<String> {
+    let mut results = Vec::new();
+    
+    MODEL_MANIFESTS.with(|storage| {
+        for (model_id, manifest_data) in storage.borrow().iter() {
+            if let Ok(manifest) = decode_record::<ModelManifest>(&manifest_data) {
+                if manifest.is_quantized() {
+                    results.push(model_id);
+                }
+            }
+        }
+    });
+    
+    results
+}
+
+// Query by compression criteria
+pub fn query_models_by_compression(min_ratio: f32) -> ModelResult<Vec<String>> {
+    let mut results = Vec::new();
+    
+    MODEL_MANIFESTS.with(|storage| {
+        for (model_id, manifest_data) in storage.borrow().iter() {
+            if let Ok(manifest) = decode_record::<ModelManifest>(&manifest_data) {
+                if let Some(ratio) = manifest.get_compression_ratio() {
+                    if ratio >= min_ratio {
+                        results.push(model_id);
+                    }
+                }
+            }
+        }
+    });
+    
+    Ok(results)
+}
+
+pub fn query_models_by_size(max_size_mb: f32) -> ModelResult<Vec<String>> {
+    let mut results = Vec::new();
+    
+    MODEL_MANIFESTS.with(|storage| {
+        for (model_id, manifest_data) in storage.borrow().iter() {
+            if let Ok(manifest) = decode_record::<ModelManifest>(&manifest_data) {
+                if let Some(size_mb) = manifest.get_size_mb() {
+                    if size_mb <= max_size_mb {
+                        results.push(model_id);
+                    }
+                }
+            }
+        }
+    });
+    
+    Ok(results)
+}
+
+// Conjunctive query over all ModelQuery filters; unset filters are ignored
+pub fn query_models(query: &ModelQuery) -> Vec<ModelManifest> {
+    let mut results = Vec::new();
+
+    MODEL_MANIFESTS.with(|storage| {
+        for (model_id, manifest_data) in storage.borrow().iter() {
+            let manifest = match decode_record::<ModelManifest>(&manifest_data) {
+                Ok(manifest) => manifest,
+                Err(_) => continue,
+            };
+
+            if let Some(compression_type) = &query.compression_type {
+                if std::mem::discriminant(&manifest.compression_type) != std::mem::discriminant(compression_type) {
+                    continue;
+                }
+            }
+
+            if let Some(min_ratio) = query.min_compression_ratio {
+                if !manifest.get_compression_ratio().is_some_and(|ratio| ratio >= min_ratio) {
+                    continue;
+                }
+            }
+
+            if let Some(min_retention) = query.min_capability_retention {
+                let retention = manifest.get_capability_retention();
+                if !retention.is_some_and(|accuracy| accuracy >= min_retention) {
+                    continue;
+                }
+            }
+
+            if let Some(max_size_mb) = query.max_size_mb {
+                if !manifest.get_size_mb().is_some_and(|size_mb| size_mb <= max_size_mb) {
+                    continue;
+                }
+            }
+
+            if let Some(min_delta) = query.min_perplexity_delta {
+                let delta = get_quality_report(&model_id).and_then(|report| report.perplexity_delta);
+                if !delta.is_some_and(|delta| delta >= min_delta) {
+                    continue;
+                }
+            }
+
+            if let Some(architecture) = &query.architecture {
+                let matches_arch = get_model_meta(&model_id)
+                    .map(|meta| &meta.arch == architecture)
+                    .unwrap_or(false);
+                if !matches_arch {
+                    continue;
+                }
+            }
+
+            results.push(manifest);
+        }
+    });
+
+    results
+}
+
+// Global statistics
+/// Running totals behind `ModelStats`. Persisted under `STATS_TOTALS_KEY`
+/// and adjusted as manifests are written or removed, so reading the global
+/// stats does not rescan every manifest.
+#[derive(CandidType, Deserialize, Default)]
+struct StatsAccumulator {
+    total_models: u64,
+    quantized_models: u64,
+    total_compression_sum: f64,
+    capability_models: u64,
+    total_capability_sum: f64,
+    total_size_saved: f64,
+}
+
+impl StatsAccumulator {
+    fn add(&mut self, manifest: &ModelManifest) {
+        self.apply(manifest, true);
+    }
+
+    fn remove(&mut self, manifest: &ModelManifest) {
+        self.apply(manifest, false);
+    }
+
+    fn apply(&mut self, manifest: &ModelManifest, adding: bool) {
+        let count = |total: &mut u64| {
+            *total = if adding { total.saturating_add(1) } else { total.saturating_sub(1) };
+        };
+        let sign = if adding { 1.0 } else { -1.0 };
+
+        count(&mut self.total_models);
+        // Aggregate every compressed type, not just NOVAQ
+        if let Some(info) = manifest.compression_info().filter(|_| manifest.is_quantized()) {
+            count(&mut self.quantized_models);
+            self.total_compression_sum += sign * info.compression_ratio as f64;
+            if let Some(retention) = info.capability_retention {
+                count(&mut self.capability_models);
+                self.total_capability_sum += sign * retention as f64;
+            }
+            
+            // Same ratio semantics as get_size_mb, so the two always agree
+            if let Some(size_mb) = manifest.get_size_mb() {
+                self.total_size_saved += sign * ((manifest.original_size_mb() - size_mb) / 1000.0) as f64;
+            }
+        }
+    }
+
+    fn finish(&self) -> ModelStats {
+        let average_compression_ratio = if self.quantized_models > 0 {
+            (self.total_compression_sum / self.quantized_models as f64) as f32
+        } else {
+            0.0
+        };
+        
+        let average_capability_retention = if self.capability_models > 0 {
+            (self.total_capability_sum / self.capability_models as f64) as f32
+        } else {
+            0.0
+        };
+        
+        let total_size_saved = self.total_size_saved.max(0.0) as f32;
+        ModelStats {
+            total_models: self.total_models,
+            quantized_models: self.quantized_models,
+            total_size_saved_gb: total_size_saved,
+            total_energy_saved: total_size_saved * get_energy_per_gb(), // kWh
+            average_compression_ratio,
+            average_capability_retention,
+        }
+    }
+}
+
+fn compute_stats_totals() -> StatsAccumulator {
+    let mut stats = StatsAccumulator::default();
+    
+    MODEL_MANIFESTS.with(|storage| {
+        for (_, manifest_data) in storage.borrow().iter() {
+            if let Ok(manifest) = decode_record::<ModelManifest>(&manifest_data) {
+                stats.add(&manifest);
+            }
+        }
+    });
+    
+    stats
+}
+
+/// Persisted totals, computed from the manifests if none are stored yet
+fn get_stats_totals() -> StatsAccumulator {
+    MODEL_STATS.with(|storage| {
+        storage
+            .borrow()
+            .get(&STATS_TOTALS_KEY.to_string())
+            .and_then(|data| decode_one::<StatsAccumulator>(&data).ok())
+    }).unwrap_or_else(compute_stats_totals)
+}
+
+fn set_stats_totals(stats: &StatsAccumulator) -> ModelResult<()> {
+    let data = encode_one(stats).map_err(|_| ModelError::InvalidFormat)?;
+    MODEL_STATS.with(|storage| {
+        storage.borrow_mut().insert(STATS_TOTALS_KEY.to_string(), data);
+    });
+    Ok(())
+}
+
+pub fn get_global_stats() -> ModelResult<ModelStats> {
+    Ok(get_stats_totals().finish())
+}
+
+/// Rebuild the persisted totals from every manifest, e.g. if they drift
+pub fn recompute_stats() -> ModelResult<ModelStats> {
+    let stats = compute_stats_totals();
+    set_stats_totals(&stats)?;
+    Ok(stats.finish())
+}
+
+/// `ModelStats` computed separately for each compression type present
+pub fn get_stats_by_compression_type() -> Vec<CompressionTypeStats> {
+    let mut buckets: Vec<(CompressionType, StatsAccumulator)> = Vec::new();
+    
+    MODEL_MANIFESTS.with(|storage| {
+        for (_, manifest_data) in storage.borrow().iter() {
+            if let Ok(manifest) = decode_record::<ModelManifest>(&manifest_data) {
+                let position = buckets.iter()
+                    .position(|(t, _)| *t == manifest.compression_type);
+                let index = position.unwrap_or_else(|| {
+                    buckets.push((manifest.compression_type.clone(), StatsAccumulator::default()));
+                    buckets.len() - 1
+                });
+                buckets[index].1.add(&manifest);
+            }
+        }
+    });
+    
+    buckets.into_iter()
+        .map(|(compression_type, stats)| CompressionTypeStats {
+            compression_type,
+            stats: stats.finish(),
+        })
+        .collect()
+}
+
+// Integrity: manifest chunk sizes that disagree with the stored bytes
+pub fn find_size_inconsistencies() -> Vec<(String, String)> {
+    let mut results = Vec::new();
+
+    MODEL_MANIFESTS.with(|storage| {
+        for (model_id, manifest_data) in storage.borrow().iter() {
+            if let Ok(manifest) = decode_record::<ModelManifest>(&manifest_data) {
+                for chunk in &manifest.chunks {
+                    let stored_len = get_chunk_for_model(&model_id, &chunk.id)
+                        .ok()
+                        .map(|data| data.len() as u64);
+                    // Missing chunks are not a size disagreement
+                    if let Some(len) = stored_len {
+                        if len != chunk.size {
+                            results.push((model_id.clone(), chunk.id.clone()));
+                        }
+                    }
+                }
+            }
+        }
+    });
+
+    results
+}
+
+// Cleanup deprecated models
+pub fn cleanup_deprecated_models() -> ModelResult<u64> {
+    let mut cleaned_count = 0u64;
+    
+    let deprecated_models: Vec<String> = MODEL_MANIFESTS.with(|storage| {
+        let mut deprecated = Vec::new();
+        for (model_id, manifest_data) in storage.borrow().iter() {
+            if let Ok(manifest) = decode_record::<ModelManifest>(&manifest_data) {
+                if matches!(manifest.state, ModelState::Deprecated) {
+                    deprecated.push(model_id);
+                }
+            }
+        }
+        deprecated
+    });
+    
+    // Remove chunks for deprecated models
+    for model_id in deprecated_models {
+        if let Ok(manifest) = get_manifest(&model_id) {
+            // Shared bytes survive until the last referencing model goes
+            for chunk in &manifest.chunks {
+                remove_chunk_for_model(&model_id, &chunk.id);
+                cleaned_count += 1;
+            }
+        }
+    }
+    
+    Ok(cleaned_count)
+}
+
+// Role persistence
+pub fn get_roles(principal: &str) -> Vec<Role> {
+    PRINCIPAL_ROLES.with(|storage| {
+        storage
+            .borrow()
+            .get(&principal.to_string())
+            .and_then(|data| decode_one::<Vec<Role>>(&data).ok())
+            .unwrap_or_default()
+    })
+}
+
+pub fn has_role(principal: &str, role: &Role) -> bool {
+    get_roles(principal).contains(role)
+}
+
+fn set_roles(principal: &str, roles: &Vec<Role>) -> ModelResult<()> {
+    PRINCIPAL_ROLES.with(|storage| {
+        let mut storage = storage.borrow_mut();
+        if roles.is_empty() {
+            storage.remove(&principal.to_string());
+        } else {
+            let data = encode_one(roles).map_err(|_| ModelError::InvalidFormat)?;
+            storage.insert(principal.to_string(), data);
+        }
+        Ok(())
+    })
+}
+
+pub fn grant_role(principal: &str, role: Role) -> ModelResult<()> {
+    let mut roles = get_roles(principal);
+    if !roles.contains(&role) {
+        roles.push(role);
+        set_roles(principal, &roles)?;
+    }
+    Ok(())
+}
+
+/// Returns false if the principal did not hold the role
+pub fn revoke_role(principal: &str, role: &Role) -> ModelResult<bool> {
+    let mut roles = get_roles(principal);
+    let before = roles.len();
+    roles.retain(|r| r != role);
+    if roles.len() == before {
+        return Ok(false);
+    }
+    set_roles(principal, &roles)?;
+    Ok(true)
+}
+
+pub fn list_principals_with_role(role: &Role) -> Vec<String> {
+    PRINCIPAL_ROLES.with(|storage| {
+        storage
+            .borrow()
+            .iter()
+            .filter(|(_, data)| decode_one::<Vec<Role>>(data).is_ok_and(|roles| roles.contains(role)))
+            .map(|(principal, _)| principal)
+            .collect()
+    })
+}
+
+/// Check the restored roles after an upgrade. Undecodable entries, or data
+/// without any admin, mean management would be locked out.
+pub fn verify_restored_roles() -> Result<(), String> {
+    let (total, decoded_admins, undecodable) = PRINCIPAL_ROLES.with(|storage| {
+        let storage = storage.borrow();
+        let mut admins = 0u64;
+        let mut undecodable = 0u64;
+        for (_, data) in storage.iter() {
+            match decode_one::<Vec<Role>>(&data) {
+                Ok(roles) if roles.contains(&Role::Admin) => admins += 1,
+                Ok(_) => {}
+                Err(_) => undecodable += 1,
+            }
+        }
+        (storage.len(), admins, undecodable)
+    });
+
+    if undecodable > 0 {
+        return Err(format!("{} of {} role entries failed to decode", undecodable, total));
+    }
+    let has_models = MODEL_MANIFESTS.with(|storage| !storage.borrow().is_empty());
+    if decoded_admins == 0 && (total > 0 || has_models) {
+        return Err(format!("No admin among {} role entries; management would be locked out", total));
+    }
+    Ok(())
+}
+
+pub fn get_schema_version() -> Option<u32> {
+    MODEL_STATS.with(|storage| {
+        storage
+            .borrow()
+            .get(&SCHEMA_VERSION_KEY.to_string())
+            .and_then(|data| decode_one::<u32>(&data).ok())
+    })
+}
+
+pub fn set_schema_version(version: u32) -> ModelResult<()> {
+    let data = encode_one(version).map_err(|_| ModelError::InvalidFormat)?;
+    MODEL_STATS.with(|storage| {
+        storage.borrow_mut().insert(SCHEMA_VERSION_KEY.to_string(), data);
+    });
+    Ok(())
+}
+
+/// Map every principal in the legacy flat uploader list to Admin and
+/// Uploader, then drop the list. Safe to call on every upgrade.
+pub fn migrate_uploaders_to_roles() -> ModelResult<()> {
+    let legacy = MODEL_STATS.with(|storage| {
+        storage.borrow().get(&AUTH_UPLOADERS_KEY.to_string())
+    });
+    let Some(data) = legacy else {
+        return Ok(());
+    };
+    // A list that exists but will not decode must not read as "no uploaders"
+    let uploaders = decode_one::<Vec<String>>(&data).map_err(|_| ModelError::InvalidFormat)?;
+
+    for principal in &uploaders {
+        grant_role(principal, Role::Admin)?;
+        grant_role(principal, Role::Uploader)?;
+    }
+    MODEL_STATS.with(|storage| {
+        storage.borrow_mut().remove(&AUTH_UPLOADERS_KEY.to_string());
+    });
+    Ok(())
+}
+
+// Audit log persistence: one event per u64 key, appended in O(log n)
+fn next_audit_index() -> u64 {
+    MODEL_STATS.with(|storage| {
+        storage
+            .borrow()
+            .get(&AUDIT_NEXT_INDEX_KEY.to_string())
+            .and_then(|data| decode_one::<u64>(&data).ok())
+            .unwrap_or(0)
+    })
+}
+
+fn set_next_audit_index(index: u64) -> ModelResult<()> {
+    let data = encode_one(index).map_err(|_| ModelError::InvalidFormat)?;
+    MODEL_STATS.with(|storage| {
+        storage.borrow_mut().insert(AUDIT_NEXT_INDEX_KEY.to_string(), data);
+    });
+    Ok(())
+}
+
+fn insert_audit_event(event: &AuditEvent) -> ModelResult<()> {
+    let data = encode_record(event)?;
+    let index = next_audit_index();
+    AUDIT_EVENTS.with(|storage| {
+        storage.borrow_mut().insert(index, data);
+    });
+    set_next_audit_index(index + 1)
+}
+
+// Import a log written in the old single-blob layout, then drop the blob.
+// The blob is only removed once it has decoded, so nothing is lost on failure.
+fn migrate_legacy_audit_log() {
+    let legacy = MODEL_STATS.with(|storage| {
+        storage
+            .borrow()
+            .get(&AUDIT_LOG_KEY.to_string())
+            .and_then(|data| decode_one::<Vec<AuditEvent>>(&data).ok())
+    });
+
+    if let Some(events) = legacy {
+        for event in &events {
+            let _ = insert_audit_event(event);
+        }
+        MODEL_STATS.with(|storage| {
+            storage.borrow_mut().remove(&AUDIT_LOG_KEY.to_string());
+        });
+    }
+}
+
+pub fn append_audit_event(event: &AuditEvent) -> ModelResult<()> {
+    migrate_legacy_audit_log();
+    insert_audit_event(event)
+}
+
+/// Events matching `filter`, decoded one entry at a time
+pub fn query_audit_log(filter: &AuditFilter) -> Vec<AuditEvent> {
+    migrate_legacy_audit_log();
+    AUDIT_EVENTS.with(|storage| {
+        storage
+            .borrow()
+            .iter()
+            .filter_map(|(_, data)| decode_record::<AuditEvent>(&data).ok())
+            .filter(|event| filter.matches(event))
+            .collect()
+    })
+}
+
+/// Events timestamped within `[from, to]` as NDJSON, reading from audit
+/// index `cursor`. Stops before the line that would pass `max_bytes`, but
+/// always includes the first line.
+pub fn export_audit_ndjson(from: u64, to: u64, cursor: u64, max_bytes: u64) -> AuditExport {
+    migrate_legacy_audit_log();
+    let mut ndjson = String::new();
+    let mut next_cursor = None;
+    AUDIT_EVENTS.with(|storage| {
+        for (index, data) in storage.borrow().range(cursor..) {
+            let Ok(event) = decode_record::<AuditEvent>(&data) else { continue };
+            if event.timestamp < from || event.timestamp > to {
+                continue;
+            }
+            let Ok(line) = serde_json::to_string(&event) else { continue };
+            if !ndjson.is_empty() && (ndjson.len() + line.len() + 1) as u64 > max_bytes {
+                next_cursor = Some(index);
+                break;
+            }
+            ndjson.push_str(&line);
+            ndjson.push('\n');
+        }
+    });
+    AuditExport { ndjson, next_cursor }
+}
+
+pub fn get_audit_log() -> Vec<AuditEvent> {
+    migrate_legacy_audit_log();
+    AUDIT_EVENTS.with(|storage| {
+        storage
+            .borrow()
+            .iter()
+            .filter_map(|(_, data)| decode_record::<AuditEvent>(&data).ok())
+            .collect()
+    })
+}
+
+// Benchmark results (all results for a model under one key)
+pub fn append_benchmark(model_id: &str, result: &BenchmarkResult) -> ModelResult<()> {
+    let mut results = get_benchmarks(model_id);
+    results.push(result.clone());
+    let data = encode_one(&results).map_err(|_| ModelError::InvalidFormat)?;
+    MODEL_BENCHMARKS.with(|storage| {
+        storage.borrow_mut().insert(model_id.to_string(), data);
+    });
+    Ok(())
+}
+
+pub fn get_benchmarks(model_id: &str) -> Vec<BenchmarkResult> {
+    MODEL_BENCHMARKS.with(|storage| {
+        storage
+            .borrow()
+            .get(&model_id.to_string())
+            .and_then(|data| decode_one::<Vec<BenchmarkResult>>(&data).ok())
+            .unwrap_or_default()
+    })
+}
+
+// Activation gates persistence
+pub fn get_activation_gates() -> Vec<ActivationGate> {
+    MODEL_STATS.with(|storage| {
+        storage
+            .borrow()
+            .get(&ACTIVATION_GATES_KEY.to_string())
+            .and_then(|data| decode_one::<Vec<ActivationGate>>(&data).ok())
+            .unwrap_or_default()
+    })
+}
+
+pub fn set_activation_gates(gates: &Vec<ActivationGate>) -> ModelResult<()> {
+    let data = encode_one(gates).map_err(|_| ModelError::InvalidFormat)?;
+    MODEL_STATS.with(|storage| {
+        storage.borrow_mut().insert(ACTIVATION_GATES_KEY.to_string(), data);
+    });
+    Ok(())
+}
+
+// Per-model access counters
+pub fn increment_access_count(model_id: &str) -> u64 {
+    ACCESS_COUNTS.with(|storage| {
+        let mut storage = storage.borrow_mut();
+        let count = storage.get(&model_id.to_string()).unwrap_or(0) + 1;
+        storage.insert(model_id.to_string(), count);
+        count
+    })
+}
+
+pub fn get_access_count(model_id: &str) -> u64 {
+    ACCESS_COUNTS.with(|storage| {
+        storage.borrow().get(&model_id.to_string()).unwrap_or(0)
+    })
+}
+
+/// The `n` most accessed models, busiest first; ties go to the lower id
+pub fn list_top_accessed(n: usize) -> Vec<(ModelId, u64)> {
+    let mut counts: Vec<(String, u64)> = ACCESS_COUNTS.with(|storage| storage.borrow().iter().collect());
+    counts.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+    counts.into_iter()
+        .take(n)
+        .map(|(model_id, count)| (ModelId(model_id), count))
+        .collect()
+}
+
+// Gini coefficient over per-model access counts (0 = uniform, toward 1 = concentrated).
+// Models that were never accessed count as zero so the whole catalog is covered.
+pub fn get_access_concentration() -> f32 {
+    let mut counts: Vec<u64> = list_models()
+        .iter()
+        .map(|id| get_access_count(id))
+        .collect();
+
+    // An empty catalog or a single model has no distribution to be unequal over
+    if counts.len() < 2 {
+        return 0.0;
+    }
+
+    let total: u64 = counts.iter().sum();
+    if total == 0 {
+        return 0.0;
+    }
+
+    counts.sort_unstable();
+    let n = counts.len() as f64;
+    let weighted_sum: f64 = counts
+        .iter()
+        .enumerate()
+        .map(|(i, &count)| (i as f64 + 1.0) * count as f64)
+        .sum();
+
+    let gini = (2.0 * weighted_sum) / (n * total as f64) - (n + 1.0) / n;
+    gini.clamp(0.0, 1.0) as f32
+}
+
+// Upload signatures
+pub fn store_signature(model_id: &str, record: &SignatureRecord) -> ModelResult<()> {
+    let data = encode_one(record).map_err(|_| ModelError::InvalidFormat)?;
+    MODEL_SIGNATURES.with(|storage| {
+        storage.borrow_mut().insert(model_id.to_string(), data);
+    });
+    Ok(())
+}
+
+pub fn get_signature(model_id: &str) -> ModelResult<SignatureRecord> {
+    MODEL_SIGNATURES.with(|storage| {
+        storage.borrow().get(&model_id.to_string())
+            .ok_or(ModelError::NotFound)
+            .and_then(|data| decode_one(&data).map_err(|_| ModelError::InvalidFormat))
+    })
+}
+
+pub fn list_signed_models() -> Vec<String> {
+    MODEL_SIGNATURES.with(|storage| {
+        storage.borrow().iter().map(|(k, _)| k).collect()
+    })
+}
+
+// Uploader public keys (raw Ed25519 bytes)
+pub fn set_uploader_key(principal: &str, public_key: Vec<u8>) {
+    UPLOADER_KEYS.with(|storage| {
+        storage.borrow_mut().insert(principal.to_string(), public_key);
+    });
+}
+
+pub fn get_uploader_key(principal: &str) -> Option<Vec<u8>> {
+    UPLOADER_KEYS.with(|storage| storage.borrow().get(&principal.to_string()))
+}
+
+pub fn remove_uploader_key(principal: &str) {
+    UPLOADER_KEYS.with(|storage| {
+        storage.borrow_mut().remove(&principal.to_string());
+    });
+}
+
+// Audited reads flag: when set, sensitive reads are only served by update endpoints
+pub fn get_audited_reads() -> bool {
+    MODEL_STATS.with(|storage| {
+        storage
+            .borrow()
+            .get(&AUDITED_READS_KEY.to_string())
+            .and_then(|data| decode_one::<bool>(&data).ok())
+            .unwrap_or(false)
+    })
+}
+
+pub fn set_audited_reads(enabled: bool) -> ModelResult<()> {
+    let data = encode_one(enabled).map_err(|_| ModelError::InvalidFormat)?;
+    MODEL_STATS.with(|storage| {
+        storage.borrow_mut().insert(AUDITED_READS_KEY.to_string(), data);
+    });
+    Ok(())
+}
+
+/// Chunk-access audit sampling: one `ChunkAccess` event is logged per this
+/// many audited reads of a model. 1 logs every read.
+pub fn get_audit_sampling() -> u32 {
+    MODEL_STATS.with(|storage| {
+        storage
+            .borrow()
+            .get(&AUDIT_SAMPLING_KEY.to_string())
+            .and_then(|data| decode_one::<u32>(&data).ok())
+            .unwrap_or(1)
+    })
+}
+
+pub fn set_audit_sampling(rate: u32) -> ModelResult<()> {
+    if rate == 0 {
+        return Err(ModelError::InvalidFormat);
+    }
+    let data = encode_one(rate).map_err(|_| ModelError::InvalidFormat)?;
+    MODEL_STATS.with(|storage| {
+        storage.borrow_mut().insert(AUDIT_SAMPLING_KEY.to_string(), data);
+    });
+    Ok(())
+}
+
+// Maintenance flag: when set, model-changing endpoints are refused
+pub fn get_maintenance_mode() -> bool {
+    MODEL_STATS.with(|storage| {
+        storage
+            .borrow()
+            .get(&MAINTENANCE_KEY.to_string())
+            .and_then(|data| decode_one::<bool>(&data).ok())
+            .unwrap_or(false)
+    })
+}
+
+pub fn set_maintenance_mode(enabled: bool) -> ModelResult<()> {
+    let data = encode_one(enabled).map_err(|_| ModelError::InvalidFormat)?;
+    MODEL_STATS.with(|storage| {
+        storage.borrow_mut().insert(MAINTENANCE_KEY.to_string(), data);
+    });
+    Ok(())
+}
+
+// Configured chunk size limit, shared by every upload path
+pub fn get_max_chunk_size() -> u64 {
+    MODEL_STATS.with(|storage| {
+        storage
+            .borrow()
+            .get(&MAX_CHUNK_SIZE_KEY.to_string())
+            .and_then(|data| decode_one::<u64>(&data).ok())
+            .unwrap_or(MAX_CHUNK_SIZE)
+    })
+}
+
+pub fn set_max_chunk_size(bytes: u64) -> ModelResult<()> {
+    if bytes == 0 || bytes > MAX_CHUNK_SIZE {
+        return Err(ModelError::InvalidFormat);
+    }
+    let data = encode_one(bytes).map_err(|_| ModelError::InvalidFormat)?;
+    MODEL_STATS.with(|storage| {
+        storage.borrow_mut().insert(MAX_CHUNK_SIZE_KEY.to_string(), data);
+    });
+    Ok(())
+}
+
+/// Quota applied to principals without an override; unlimited until set
+pub fn get_default_upload_quota() -> UploadQuota {
+    MODEL_STATS.with(|storage| {
+        storage
+            .borrow()
+            .get(&DEFAULT_UPLOAD_QUOTA_KEY.to_string())
+            .and_then(|data| decode_one::<UploadQuota>(&data).ok())
+            .unwrap_or_default()
+    })
+}
+
+pub fn set_default_upload_quota(quota: &UploadQuota) -> ModelResult<()> {
+    let data = encode_one(quota).map_err(|_| ModelError::InvalidFormat)?;
+    MODEL_STATS.with(|storage| {
+        storage.borrow_mut().insert(DEFAULT_UPLOAD_QUOTA_KEY.to_string(), data);
+    });
+    Ok(())
+}
+
+/// The principal's override if one is set, else the default quota
+pub fn get_upload_quota(principal: &str) -> UploadQuota {
+    UPLOAD_QUOTAS.with(|storage| {
+        storage
+            .borrow()
+            .get(&principal.to_string())
+            .and_then(|data| decode_one::<UploadQuota>(&data).ok())
+    })
+    .unwrap_or_else(get_default_upload_quota)
+}
+
+/// Set or (with None) clear a principal's quota override
+pub fn set_upload_quota(principal: &str, quota: Option<&UploadQuota>) -> ModelResult<()> {
+    UPLOAD_QUOTAS.with(|storage| {
+        let mut storage = storage.borrow_mut();
+        match quota {
+            Some(quota) => {
+                let data = encode_one(quota).map_err(|_| ModelError::InvalidFormat)?;
+                storage.insert(principal.to_string(), data);
+            }
+            None => {
+                storage.remove(&principal.to_string());
+            }
+        }
+        Ok(())
+    })
+}
+
+/// Ratio a submitted NOVAQ model must exceed; 1.0 until an admin raises it
+pub fn get_min_compression_ratio() -> f32 {
+    MODEL_STATS.with(|storage| {
+        storage
+            .borrow()
+            .get(&MIN_COMPRESSION_RATIO_KEY.to_string())
+            .and_then(|data| decode_one::<f32>(&data).ok())
+            .unwrap_or(DEFAULT_MIN_COMPRESSION_RATIO)
+    })
+}
+
+/// The floor cannot go below 1.0, which would admit models that do not compress
+pub fn set_min_compression_ratio(ratio: f32) -> ModelResult<()> {
+    if !ratio.is_finite() || ratio < DEFAULT_MIN_COMPRESSION_RATIO {
+        return Err(ModelError::InvalidFormat);
+    }
+    let data = encode_one(ratio).map_err(|_| ModelError::InvalidFormat)?;
+    MODEL_STATS.with(|storage| {
+        storage.borrow_mut().insert(MIN_COMPRESSION_RATIO_KEY.to_string(), data);
+    });
+    Ok(())
+}
+
+/// Ratio a model needs before it may hold the HighCompression badge
+pub fn get_high_compression_threshold() -> f32 {
+    MODEL_STATS.with(|storage| {
+        storage
+            .borrow()
+            .get(&HIGH_COMPRESSION_THRESHOLD_KEY.to_string())
+            .and_then(|data| decode_one::<f32>(&data).ok())
+            .unwrap_or(DEFAULT_HIGH_COMPRESSION_THRESHOLD)
+    })
+}
+
+pub fn set_high_compression_threshold(ratio: f32) -> ModelResult<()> {
+    if !ratio.is_finite() || ratio <= DEFAULT_MIN_COMPRESSION_RATIO {
+        return Err(ModelError::InvalidFormat);
+    }
+    let data = encode_one(ratio).map_err(|_| ModelError::InvalidFormat)?;
+    MODEL_STATS.with(|storage| {
+        storage.borrow_mut().insert(HIGH_COMPRESSION_THRESHOLD_KEY.to_string(), data);
+    });
+    Ok(())
+}
+
+// Energy coefficient in kWh per GB saved
+pub fn get_energy_per_gb() -> f32 {
+    MODEL_STATS.with(|storage| {
+        storage
+            .borrow()
+            .get(&ENERGY_PER_GB_KEY.to_string())
+            .and_then(|data| decode_one::<f32>(&data).ok())
+            .unwrap_or(DEFAULT_ENERGY_PER_GB_KWH)
+    })
+}
+
+pub fn set_energy_per_gb(kwh: f32) -> ModelResult<()> {
+    if !kwh.is_finite() || kwh < 0.0 {
+        return Err(ModelError::InvalidFormat);
+    }
+    let data = encode_one(kwh).map_err(|_| ModelError::InvalidFormat)?;
+    MODEL_STATS.with(|storage| {
+        storage.borrow_mut().insert(ENERGY_PER_GB_KEY.to_string(), data);
+    });
+    Ok(())
+}
+
+/// Energy saved by one model, from its recorded original size and the bytes
+/// it is actually stored as. None when the original size is unknown.
+pub fn estimate_energy_savings(model_id: &str) -> Option<EnergyReport> {
+    let manifest = get_manifest(model_id).ok()?;
+    let original_size_bytes = manifest.compression_info()?.original_size_bytes?;
+    let compressed_size_bytes = manifest.total_chunk_bytes();
+    let saved_bytes = original_size_bytes.saturating_sub(compressed_size_bytes);
+    let energy_per_gb_kwh = get_energy_per_gb();
+    Some(EnergyReport {
+        model_id: manifest.model_id,
+        original_size_bytes,
+        compressed_size_bytes,
+        saved_bytes,
+        energy_per_gb_kwh,
+        energy_saved_kwh: (saved_bytes as f64 / 1e9 * energy_per_gb_kwh as f64) as f32,
+    })
+}
+
+// Whether activations go through governance; on unless switched off
+pub fn get_governance_enabled() -> bool {
+    MODEL_STATS.with(|storage| {
+        storage
+            .borrow()
+            .get(&GOVERNANCE_ENABLED_KEY.to_string())
+            .and_then(|data| decode_one::<bool>(&data).ok())
+            .unwrap_or(true)
+    })
+}
+
+pub fn set_governance_enabled(enabled: bool) -> ModelResult<()> {
+    let data = encode_one(enabled).map_err(|_| ModelError::InvalidFormat)?;
+    MODEL_STATS.with(|storage| {
+        storage.borrow_mut().insert(GOVERNANCE_ENABLED_KEY.to_string(), data);
+    });
+    Ok(())
+}
+
+/// Days a deprecated model keeps its manifest before the heartbeat removes
+/// it; 0 (the default) keeps deprecated models indefinitely
+pub fn get_deprecation_retention() -> u64 {
+    MODEL_STATS.with(|storage| {
+        storage
+            .borrow()
+            .get(&DEPRECATION_RETENTION_KEY.to_string())
+            .and_then(|data| decode_one::<u64>(&data).ok())
+            .unwrap_or(0)
+    })
+}
+
+pub fn set_deprecation_retention(days: u64) -> ModelResult<()> {
+    let data = encode_one(days).map_err(|_| ModelError::InvalidFormat)?;
+    MODEL_STATS.with(|storage| {
+        storage.borrow_mut().insert(DEPRECATION_RETENTION_KEY.to_string(), data);
+    });
+    Ok(())
+}
+
+// Heartbeat maintenance interval in seconds; 0 disables the sweeps
+pub fn get_maintenance_interval() -> Option<u64> {
+    MODEL_STATS.with(|storage| {
+        storage
+            .borrow()
+            .get(&MAINTENANCE_INTERVAL_KEY.to_string())
+            .and_then(|data| decode_one::<u64>(&data).ok())
+    })
+}
+
+pub fn set_maintenance_interval(seconds: u64) -> ModelResult<()> {
+    let data = encode_one(seconds).map_err(|_| ModelError::InvalidFormat)?;
+    MODEL_STATS.with(|storage| {
+        storage.borrow_mut().insert(MAINTENANCE_INTERVAL_KEY.to_string(), data);
+    });
+    Ok(())
+}
+
+// Integrity sweep progress
+pub fn get_sweep_status() -> Option<SweepStatus> {
+    MODEL_STATS.with(|storage| {
+        storage
+            .borrow()
+            .get(&INTEGRITY_SWEEP_KEY.to_string())
+            .and_then(|data| decode_one::<SweepStatus>(&data).ok())
+    })
+}
+
+pub fn set_sweep_status(status: &SweepStatus) -> ModelResult<()> {
+    let data = encode_one(status).map_err(|_| ModelError::InvalidFormat)?;
+    MODEL_STATS.with(|storage| {
+        storage.borrow_mut().insert(INTEGRITY_SWEEP_KEY.to_string(), data);
+    });
+    Ok(())
+}
+
+// Idempotency keys for submit_model retries
+pub fn get_idempotency_record(key: &str) -> Option<IdempotencyRecord> {
+    IDEMPOTENCY_KEYS.with(|storage| {
+        storage
+            .borrow()
+            .get(&key.to_string())
+            .and_then(|data| decode_one::<IdempotencyRecord>(&data).ok())
+    })
+}
+
+/// Record `key`, first dropping entries older than `expires_before` and,
+/// if still at `max_entries`, the oldest ones, so the store stays bounded
+pub fn record_idempotency_key(
+    key: &str,
+    record: &IdempotencyRecord,
+    expires_before: u64,
+    max_entries: usize,
+) -> ModelResult<()> {
+    let data = encode_one(record).map_err(|_| ModelError::InvalidFormat)?;
+    IDEMPOTENCY_KEYS.with(|storage| {
+        let mut storage = storage.borrow_mut();
+        let mut entries: Vec<(String, u64)> = storage
+            .iter()
+            .map(|(k, v)| {
+                let recorded_at = decode_one::<IdempotencyRecord>(&v).map_or(0, |r| r.recorded_at);
+                (k, recorded_at)
+            })
+            .collect();
+        entries.sort_by_key(|(_, recorded_at)| *recorded_at);
+
+        let mut remaining = entries.len();
+        for (k, recorded_at) in entries {
+            if recorded_at >= expires_before && remaining < max_entries {
+                break;
+            }
+            storage.remove(&k);
+            remaining -= 1;
+        }
+        storage.insert(key.to_string(), data);
+    });
+    Ok(())
+}
+
+// Legacy governance snapshot, removed once read so it is migrated only once
+pub fn take_legacy_governance_state() -> Option<GovernanceEngine> {
+    MODEL_STATS.with(|storage| {
+        let mut storage = storage.borrow_mut();
+        let data = storage.remove(&GOVERNANCE_KEY.to_string())?;
+        decode_one::<GovernanceEngine>(&data).ok()
+    })
+}
+
+// Governance proposals, one entry per proposal id
+pub fn store_proposal(proposal: &GovernanceProposal) -> ModelResult<()> {
+    let data = encode_one(proposal).map_err(|_| ModelError::InvalidFormat)?;
+    GOVERNANCE_PROPOSALS.with(|storage| {
+        storage.borrow_mut().insert(proposal.id, data);
+    });
+    Ok(())
+}
+
+pub fn list_stored_proposals() -> Vec<GovernanceProposal> {
+    GOVERNANCE_PROPOSALS.with(|storage| {
+        storage
+            .borrow()
+            .iter()
+            .filter_map(|(_, data)| decode_one::<GovernanceProposal>(&data).ok())
+            .collect()
+    })
+}
+
+pub fn get_next_proposal_id() -> Option<u64> {
+    MODEL_STATS.with(|storage| {
+        storage
+            .borrow()
+            .get(&GOVERNANCE_NEXT_ID_KEY.to_string())
+            .and_then(|data| decode_one::<u64>(&data).ok())
+    })
+}
+
+pub fn set_next_proposal_id(next_id: u64) -> ModelResult<()> {
+    let data = encode_one(next_id).map_err(|_| ModelError::InvalidFormat)?;
+    MODEL_STATS.with(|storage| {
+        storage.borrow_mut().insert(GOVERNANCE_NEXT_ID_KEY.to_string(), data);
+    });
+    Ok(())
+}
+
+pub fn get_governance_config() -> Option<GovernanceConfig> {
+    MODEL_STATS.with(|storage| {
+        storage
+            .borrow()
+            .get(&GOVERNANCE_CONFIG_KEY.to_string())
+            .and_then(|data| decode_one::<GovernanceConfig>(&data).ok())
+    })
+}
+
+pub fn set_governance_config(config: &GovernanceConfig) -> ModelResult<()> {
+    let data = encode_one(config).map_err(|_| ModelError::InvalidFormat)?;
+    MODEL_STATS.with(|storage| {
+        storage.borrow_mut().insert(GOVERNANCE_CONFIG_KEY.to_string(), data);
+    });
+    Ok(())
+}
+
+// Rate limit configuration (written on every change)
+pub fn get_rate_limit_config() -> Option<RateLimitConfig> {
+    MODEL_STATS.with(|storage| {
+        storage
+            .borrow()
+            .get(&RATE_LIMITS_KEY.to_string())
+            .and_then(|data| decode_one::<RateLimitConfig>(&data).ok())
+    })
+}
 
-// Model manifest storage
-pub fn store_manifest(model_id: &str, manifest: &ModelManifest) -> ModelResult<()> {
-    let manifest_data = encode_one(manifest).map_err(|_| ModelError::InvalidFormat)?;
-    
-    MODEL_MANIFESTS.with(|storage| {
-        storage.borrow_mut().insert(model_id.to_string(), manifest_data);
+pub fn set_rate_limit_config(config: &RateLimitConfig) -> ModelResult<()> {
+    let data = encode_one(config).map_err(|_| ModelError::InvalidFormat)?;
+    MODEL_STATS.with(|storage| {
+        storage.borrow_mut().insert(RATE_LIMITS_KEY.to_string(), data);
     });
-    
     Ok(())
 }
 
-pub fn get_manifest(model_id: &str) -> ModelResult<ModelManifest> {
+/// Recount the model state metrics from stored manifests, correcting any
+/// drift in the incremental counters
+pub fn refresh_model_counts() {
+    let (mut active, mut pending, mut deprecated) = (0u64, 0u64, 0u64);
     MODEL_MANIFESTS.with(|storage| {
-        storage.borrow().get(&model_id.to_string())
-            .ok_or(ModelError::NotFound)
-            .and_then(|data| decode_one(&data).map_err(|_| ModelError::InvalidFormat))
+        for (_, manifest_data) in storage.borrow().iter() {
+            if let Ok(manifest) = decode_record::<ModelManifest>(&manifest_data) {
+                match manifest.state {
+                    ModelState::Active => active += 1,
+                    ModelState::Pending => pending += 1,
+                    ModelState::Deprecated => deprecated += 1,
+                }
+            }
+        }
+    });
+    metrics::update_model_counts(active, pending, deprecated);
+}
+
+// Metrics snapshot (written in pre_upgrade)
+pub fn get_metrics_snapshot() -> Option<Metrics> {
+    MODEL_STATS.with(|storage| {
+        storage
+            .borrow()
+            .get(&METRICS_KEY.to_string())
+            .and_then(|data| decode_one::<Metrics>(&data).ok())
     })
 }
 
-// Model metadata storage
-pub fn store_model_meta(model_id: &str, meta: &ModelMeta) -> ModelResult<()> {
-    let meta_data = encode_one(meta).map_err(|_| ModelError::InvalidFormat)?;
-    
-    MODEL_METADATA.with(|storage| {
-        storage.borrow_mut().insert(model_id.to_string(), meta_data);
+pub fn set_metrics_snapshot(metrics: &Metrics) -> ModelResult<()> {
+    let data = encode_one(metrics).map_err(|_| ModelError::InvalidFormat)?;
+    MODEL_STATS.with(|storage| {
+        storage.borrow_mut().insert(METRICS_KEY.to_string(), data);
     });
-    
     Ok(())
 }
 
-pub fn get_model_meta(model_id: &str) -> ModelResult<ModelMeta> {
-    MODEL_METADATA.with(|storage| {
-        storage.borrow().get(&model_id.to_string())
-            .ok_or(ModelError::NotFound)
-            .and_then(|data| decode_one(&data).map_err(|_| ModelError::InvalidFormat))
+// Badge persistence
+pub fn get_badges(model_id: &str) -> Vec<Badge> {
+    MODEL_BADGES.with(|storage| {
+        storage
+            .borrow()
+            .get(&model_id.to_string())
+            .and_then(|data| decode_one::<Vec<Badge>>(&data).ok())
+            .unwrap_or_default()
     })
 }
 
-// Chunk storage (namespaced by model)
-pub fn store_chunk_for_model(model_id: &str, chunk_id: &str, chunk_data: Vec<u8>) -> ModelResult<()> {
-    // Validate chunk size
-    if chunk_data.len() > 2_097_152 { // 2 MiB limit
-        return Err(ModelError::StorageFull);
+pub fn set_badges(model_id: &str, badges: &Vec<Badge>) -> ModelResult<()> {
+    for previous in get_badges(model_id) {
+        if !badges.iter().any(|b| b.badge_type == previous.badge_type) {
+            remove_from_index(&badge_index_key(&previous.badge_type), model_id)?;
+        }
     }
-    
-    CHUNK_STORAGE.with(|storage| {
-        storage.borrow_mut().insert(chunk_key(model_id, chunk_id), chunk_data);
+    for badge in badges {
+        add_to_index(&badge_index_key(&badge.badge_type), model_id)?;
+    }
+
+    MODEL_BADGES.with(|storage| {
+        let mut storage = storage.borrow_mut();
+        if badges.is_empty() {
+            storage.remove(&model_id.to_string());
+        } else {
+            let data = encode_one(badges).map_err(|_| ModelError::InvalidFormat)?;
+            storage.insert(model_id.to_string(), data);
+        }
+        Ok(())
+    })
+}
+
+// Staged upload sessions
+pub fn next_upload_session_id() -> ModelResult<String> {
+    MODEL_STATS.with(|storage| {
+        let mut storage = storage.borrow_mut();
+        let seq = storage
+            .get(&UPLOAD_SESSION_SEQ_KEY.to_string())
+            .and_then(|data| decode_one::<u64>(&data).ok())
+            .unwrap_or(0);
+        let data = encode_one(seq + 1).map_err(|_| ModelError::InvalidFormat)?;
+        storage.insert(UPLOAD_SESSION_SEQ_KEY.to_string(), data);
+        Ok(format!("upload-{}", seq))
+    })
+}
+
+pub fn store_upload_session(session: &UploadSession) -> ModelResult<()> {
+    let data = encode_one(session).map_err(|_| ModelError::InvalidFormat)?;
+    UPLOAD_SESSIONS.with(|storage| {
+        storage.borrow_mut().insert(session.session_id.clone(), data);
     });
-    
     Ok(())
 }
 
-pub fn get_chunk_for_model(model_id: &str, chunk_id: &str) -> ModelResult<Vec<u8>> {
-    CHUNK_STORAGE.with(|storage| {
-        storage.borrow().get(&chunk_key(model_id, chunk_id))
+pub fn get_upload_session(session_id: &str) -> ModelResult<UploadSession> {
+    UPLOAD_SESSIONS.with(|storage| {
+        storage.borrow().get(&session_id.to_string())
+            .and_then(|data| decode_one::<UploadSession>(&data).ok())
             .ok_or(ModelError::NotFound)
     })
 }
 
-// Model listing and queries
-pub fn list_models() -> Vec<String> {
-    MODEL_MANIFESTS.with(|storage| {
-        storage.borrow().iter().map(|(k, _)| k).collect()
+pub fn list_upload_sessions() -> Vec<UploadSession> {
+    UPLOAD_SESSIONS.with(|storage| {
+        storage
+            .borrow()
+            .iter()
+            .filter_map(|(_, data)| decode_one::<UploadSession>(&data).ok())
+            .collect()
     })
 }
 
-pub fn list_quantized_models() -> Vec<String> {
-    let mut results = Vec::new();
-    
-    MODEL_MANIFESTS.with(|storage| {
-        for (model_id, manifest_data) in storage.borrow().iter() {
-            if let Ok(manifest) = decode_one::<ModelManifest>(&manifest_data) {
-                if manifest.is_quantized() {
-                    results.push(model_id);
-                }
-            }
+/// Remove a session and every chunk staged under it
+pub fn remove_upload_session(session_id: &str) {
+    UPLOAD_SESSIONS.with(|storage| {
+        storage.borrow_mut().remove(&session_id.to_string());
+    });
+    let prefix = format!("{}:", session_id);
+    STAGED_CHUNKS.with(|storage| {
+        let mut storage = storage.borrow_mut();
+        let keys: Vec<String> = storage
+            .range(prefix.clone()..)
+            .take_while(|(k, _)| k.starts_with(&prefix))
+            .map(|(k, _)| k)
+            .collect();
+        for key in keys {
+            storage.remove(&key);
         }
     });
-    
-    results
 }
 
-// Query by compression criteria
-pub fn query_models_by_compression(min_ratio: f32) -> ModelResult<Vec<String>> {
-    let mut results = Vec::new();
-    
-    MODEL_MANIFESTS.with(|storage| {
-        for (model_id, manifest_data) in storage.borrow().iter() {
-            if let Ok(manifest) = decode_one::<ModelManifest>(&manifest_data) {
-                if let Some(ratio) = manifest.get_compression_ratio() {
-                    if ratio >= min_ratio {
-                        results.push(model_id);
-                    }
-                }
-            }
-        }
+pub fn store_staged_chunk(session_id: &str, chunk_id: &str, data: Vec<u8>) {
+    STAGED_CHUNKS.with(|storage| {
+        storage.borrow_mut().insert(chunk_key(session_id, chunk_id), data);
     });
-    
-    Ok(results)
 }
 
-pub fn query_models_by_size(max_size_mb: f32) -> ModelResult<Vec<String>> {
-    let mut results = Vec::new();
-    
-    MODEL_MANIFESTS.with(|storage| {
-        for (model_id, manifest_data) in storage.borrow().iter() {
-            if let Ok(manifest) = decode_one::<ModelManifest>(&manifest_data) {
-                if let Some(size_mb) = manifest.get_size_mb() {
-                    if size_mb <= max_size_mb {
-                        results.push(model_id);
-                    }
-                }
-            }
+pub fn get_staged_chunk(session_id: &str, chunk_id: &str) -> ModelResult<Vec<u8>> {
+    STAGED_CHUNKS.with(|storage| {
+        storage.borrow().get(&chunk_key(session_id, chunk_id))
+            .ok_or(ModelError::NotFound)
+    })
+}
+
+pub fn get_model_full(model_id: &str) -> Option<ModelFull> {
+    let manifest = get_manifest(model_id).ok()?;
+    let meta = get_model_meta(model_id).ok()?;
+
+    Some(ModelFull {
+        total_size_bytes: manifest.chunks.iter().map(|c| c.size).sum(),
+        compression_ratio: manifest.get_compression_ratio(),
+        badges: get_badges(model_id),
+        manifest,
+        meta,
+    })
+}
+
+/// Walk the `supersedes` chain from a model back to its first version
+pub fn get_version_history(model_id: &str) -> Vec<ModelManifest> {
+    let mut history: Vec<ModelManifest> = Vec::new();
+    let mut next = Some(model_id.to_string());
+    while let Some(id) = next {
+        // Guard against a cycle written by a corrupt manifest
+        if history.iter().any(|m| m.model_id.0 == id) {
+            break;
         }
+        let Ok(manifest) = get_manifest(&id) else { break };
+        next = manifest.supersedes.as_ref().map(|p| p.0.clone());
+        history.push(manifest);
+    }
+    history
+}
+
+/// The manifest in `model_id`'s version chain whose `version` matches
+pub fn get_manifest_by_version(model_id: &str, version: &str) -> Option<ModelManifest> {
+    get_version_history(model_id)
+        .into_iter()
+        .find(|manifest| manifest.version == version)
+}
+
+// Verification records
+pub fn store_verification(model_id: &str, record: &VerificationRecord) -> ModelResult<()> {
+    let data = encode_one(record).map_err(|_| ModelError::InvalidFormat)?;
+    MODEL_VERIFICATIONS.with(|storage| {
+        storage.borrow_mut().insert(model_id.to_string(), data);
     });
-    
-    Ok(results)
+    Ok(())
 }
 
-// Global statistics
-pub fn get_global_stats() -> ModelResult<ModelStats> {
-    let mut total_models = 0u64;
-    let mut quantized_models = 0u64;
-    let mut total_compression_sum = 0.0f32;
-    let mut total_capability_sum = 0.0f32;
-    let mut total_size_saved = 0.0f32;
-    
-    MODEL_MANIFESTS.with(|storage| {
-        total_models = storage.borrow().len() as u64;
-        
-        for (_, manifest_data) in storage.borrow().iter() {
-            if let Ok(manifest) = decode_one::<ModelManifest>(&manifest_data) {
-                if let Some(quantized_model) = &manifest.quantized_model {
-                    quantized_models += 1;
-                    total_compression_sum += quantized_model.compression_ratio;
-                    total_capability_sum += quantized_model.bit_accuracy;
-                    
-                    // Calculate size saved (estimated)
-                    let estimated_original_size_gb = 8.0; // 8GB typical for large models
-                    let estimated_compressed_size_gb = estimated_original_size_gb / quantized_model.compression_ratio;
-                    total_size_saved += estimated_original_size_gb - estimated_compressed_size_gb;
-                }
-            }
-        }
+// Uploader-attested quality reports
+pub fn store_quality_report(model_id: &str, report: &NOVAQVerificationReport) -> ModelResult<()> {
+    let data = encode_one(report).map_err(|_| ModelError::InvalidFormat)?;
+    MODEL_QUALITY_REPORTS.with(|storage| {
+        storage.borrow_mut().insert(model_id.to_string(), data);
     });
-    
-    let average_compression_ratio = if quantized_models > 0 {
-        total_compression_sum / quantized_models as f32
-    } else {
-        0.0
-    };
-    
-    let average_capability_retention = if quantized_models > 0 {
-        total_capability_sum / quantized_models as f32
-    } else {
-        0.0
-    };
-    
-    Ok(ModelStats {
-        total_models,
-        quantized_models,
-        total_size_saved_gb: total_size_saved,
-        total_energy_saved: total_size_saved * 71.0, // Estimated energy savings
-        average_compression_ratio,
-        average_capability_retention,
+    Ok(())
+}
+
+pub fn get_quality_report(model_id: &str) -> Option<NOVAQVerificationReport> {
+    MODEL_QUALITY_REPORTS.with(|storage| {
+        storage
+            .borrow()
+            .get(&model_id.to_string())
+            .and_then(|data| decode_one::<NOVAQVerificationReport>(&data).ok())
     })
 }
 
-// Cleanup deprecated models
-pub fn cleanup_deprecated_models() -> ModelResult<u64> {
-    let mut cleaned_count = 0u64;
-    
-    let deprecated_models: Vec<String> = MODEL_MANIFESTS.with(|storage| {
-        let mut deprecated = Vec::new();
-        for (model_id, manifest_data) in storage.borrow().iter() {
-            if let Ok(manifest) = decode_one::<ModelManifest>(&manifest_data) {
-                if matches!(manifest.state, ModelState::Deprecated) {
-                    deprecated.push(model_id);
-                }
-            }
-        }
-        deprecated
+pub fn remove_quality_report(model_id: &str) {
+    MODEL_QUALITY_REPORTS.with(|storage| {
+        storage.borrow_mut().remove(&model_id.to_string());
     });
-    
-    // Remove chunks for deprecated models
-    for model_id in deprecated_models {
-        if let Ok(manifest) = get_manifest(&model_id) {
-            for chunk in &manifest.chunks {
-                CHUNK_STORAGE.with(|storage| {
-                    storage.borrow_mut().remove(&chunk_key(&model_id, &chunk.id));
-                });
-                cleaned_count += 1;
-            }
-        }
-    }
-    
-    Ok(cleaned_count)
 }
 
-// Authorized uploaders persistence
-pub fn get_authorized_uploaders() -> Vec<String> {
-    MODEL_STATS.with(|storage| {
+// Reproducibility reports
+pub fn store_reproducibility(model_id: &str, report: &ReproducibilityReport) -> ModelResult<()> {
+    let data = encode_one(report).map_err(|_| ModelError::InvalidFormat)?;
+    MODEL_REPRODUCIBILITY.with(|storage| {
+        storage.borrow_mut().insert(model_id.to_string(), data);
+    });
+    Ok(())
+}
+
+pub fn get_reproducibility(model_id: &str) -> Option<ReproducibilityReport> {
+    MODEL_REPRODUCIBILITY.with(|storage| {
         storage
             .borrow()
-            .get(&AUTH_UPLOADERS_KEY.to_string())
-            .and_then(|data| decode_one::<Vec<String>>(&data).ok())
-            .unwrap_or_default()
+            .get(&model_id.to_string())
+            .and_then(|data| decode_one::<ReproducibilityReport>(&data).ok())
     })
 }
 
-pub fn set_authorized_uploaders(uploaders: &Vec<String>) -> ModelResult<()> {
-    let data = encode_one(uploaders).map_err(|_| ModelError::InvalidFormat)?;
-    MODEL_STATS.with(|storage| {
-        storage.borrow_mut().insert(AUTH_UPLOADERS_KEY.to_string(), data);
+pub fn remove_reproducibility(model_id: &str) {
+    MODEL_REPRODUCIBILITY.with(|storage| {
+        storage.borrow_mut().remove(&model_id.to_string());
     });
-    Ok(())
 }
 
-// Audit log persistence (simple append whole vector)
-pub fn append_audit_event(event: &AuditEvent) -> ModelResult<()> {
-    let mut log = get_audit_log();
-    log.push(event.clone());
-    let data = encode_one(&log).map_err(|_| ModelError::InvalidFormat)?;
-    MODEL_STATS.with(|storage| {
-        storage.borrow_mut().insert(AUDIT_LOG_KEY.to_string(), data);
+pub fn remove_verification(model_id: &str) {
+    MODEL_VERIFICATIONS.with(|storage| {
+        storage.borrow_mut().remove(&model_id.to_string());
     });
-    Ok(())
 }
 
-pub fn get_audit_log() -> Vec<AuditEvent> {
-    MODEL_STATS.with(|storage| {
+pub fn get_verification(model_id: &str) -> Option<VerificationRecord> {
+    MODEL_VERIFICATIONS.with(|storage| {
         storage
             .borrow()
-            .get(&AUDIT_LOG_KEY.to_string())
-            .and_then(|data| decode_one::<Vec<AuditEvent>>(&data).ok())
-            .unwrap_or_default()
+            .get(&model_id.to_string())
+            .and_then(|data| decode_one::<VerificationRecord>(&data).ok())
     })
-}
\ No newline at end of file
+}
+
+pub fn get_storage_usage() -> StorageUsage {
+    let mut models = Vec::new();
+    let mut seen_hashes = std::collections::HashSet::new();
+    let mut chunk_bytes = 0u64;
+    let mut manifest_bytes = 0u64;
+    let mut total_chunks = 0u64;
+
+    MODEL_MANIFESTS.with(|storage| {
+        for (model_id, manifest_data) in storage.borrow().iter() {
+            manifest_bytes += manifest_data.len() as u64;
+            if let Ok(manifest) = decode_record::<ModelManifest>(&manifest_data) {
+                for chunk in &manifest.chunks {
+                    if seen_hashes.insert(chunk.sha256.clone()) {
+                        chunk_bytes += chunk.size;
+                    }
+                }
+                total_chunks += manifest.chunks.len() as u64;
+                models.push(ModelStorageUsage {
+                    model_id,
+                    bytes: manifest.chunks.iter().map(|c| c.size).sum(),
+                    chunks: manifest.chunks.len() as u64,
+                });
+            }
+        }
+    });
+
+    let metadata_bytes = MODEL_METADATA.with(|storage| {
+        storage.borrow().iter().map(|(_, data)| data.len() as u64).sum::<u64>()
+    });
+
+    let largest_model = models.iter()
+        .max_by_key(|m| m.bytes)
+        .map(|m| m.model_id.clone());
+
+    StorageUsage {
+        total_bytes: chunk_bytes + manifest_bytes + metadata_bytes,
+        chunk_bytes,
+        manifest_bytes,
+        metadata_bytes,
+        total_chunks,
+        largest_model,
+        stored_chunk_bytes: get_stored_bytes(),
+        capacity_bytes: get_storage_capacity(),
+        models,
+    }
+}
+
+/// Chunk info in a model's manifest whose content hash matches `sha256`
+pub fn find_chunk_by_hash(model_id: &str, sha256: &str) -> Option<ChunkInfo> {
+    let manifest = get_manifest(model_id).ok()?;
+    manifest.chunks.into_iter()
+        .find(|chunk| chunk.sha256.eq_ignore_ascii_case(sha256))
+}
+
+/// Remove every record kept for a model: chunks, manifest, meta (and its
+/// index entries), badges, signature, benchmarks, verification and access
+/// count. The audit trail is left intact.
+pub fn delete_model_records(manifest: &ModelManifest) -> ModelResult<()> {
+    let model_id = manifest.model_id.0.as_str();
+    for chunk in &manifest.chunks {
+        remove_chunk_for_model(model_id, &chunk.id);
+    }
+    if let Ok(meta) = get_model_meta(model_id) {
+        unindex_model_meta(model_id, &meta)?;
+    }
+
+    let key = model_id.to_string();
+    MODEL_METADATA.with(|storage| storage.borrow_mut().remove(&key));
+    set_badges(model_id, &Vec::new())?;
+    set_model_tags(model_id, &Vec::new())?;
+    set_access_list(model_id, None)?;
+    MODEL_SIGNATURES.with(|storage| storage.borrow_mut().remove(&key));
+    MODEL_BENCHMARKS.with(|storage| storage.borrow_mut().remove(&key));
+    MODEL_VERIFICATIONS.with(|storage| storage.borrow_mut().remove(&key));
+    MODEL_REPRODUCIBILITY.with(|storage| storage.borrow_mut().remove(&key));
+    MODEL_QUALITY_REPORTS.with(|storage| storage.borrow_mut().remove(&key));
+    ACCESS_COUNTS.with(|storage| storage.borrow_mut().remove(&key));
+    // Manifest last, so a trap part-way leaves the model findable for a retry
+    let mut stats = get_stats_totals();
+    if MODEL_MANIFESTS.with(|storage| storage.borrow_mut().remove(&key)).is_some() {
+        metrics::record_state_change(Some(&manifest.state), None);
+        stats.remove(manifest);
+        set_stats_totals(&stats)?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn manifest(model_id: &str, state: ModelState) -> ModelManifest {
+        ModelManifest {
+            model_id: ModelId(model_id.to_string()),
+            version: "1.0".to_string(),
+            chunks: vec![ChunkInfo {
+                id: "chunk-0".to_string(),
+                offset: 0,
+                size: 4,
+                sha256: String::new(),
+                codec: None,
+                stored_size: None,
+            }],
+            digest: String::new(),
+            state,
+            uploaded_at: 0,
+            uploader: Some("uploader".to_string()),
+            activated_at: None,
+            deprecated_at: None,
+            serve_after: None,
+            supersedes: None,
+            compression_type: CompressionType::Uncompressed,
+            compression: None,
+            novaq_config: None,
+            quantized_model: None,
+        }
+    }
+
+    fn meta(family: &str, arch: &str) -> ModelMeta {
+        ModelMeta {
+            family: family.to_string(),
+            arch: arch.to_string(),
+            tokenizer_id: "bpe".to_string(),
+            vocab_size: 32_000,
+            ctx_window: 4096,
+            license: "MIT".to_string(),
+            quantization_info: QuantizationInfo {
+                method: "none".to_string(),
+                quantizer_version: "1".to_string(),
+                quantization_date: 0,
+                source_model: family.to_string(),
+            },
+        }
+    }
+
+    fn compressed(model_id: &str, compression_type: CompressionType, ratio: f32, original_bytes: u64) -> ModelManifest {
+        let mut model = manifest(model_id, ModelState::Active);
+        model.compression_type = compression_type;
+        model.compression = Some(CompressionInfo {
+            compression_ratio: ratio,
+            capability_retention: Some(95.0),
+            bits_per_weight: None,
+            original_size_bytes: Some(original_bytes),
+        });
+        model
+    }
+
+    fn store_models_with_accesses(counts: &[u64]) {
+        for (i, &count) in counts.iter().enumerate() {
+            let id = format!("m{}", i);
+            store_manifest(&id, &manifest(&id, ModelState::Active)).unwrap();
+            for _ in 0..count {
+                increment_access_count(&id);
+            }
+        }
+    }
+
+    #[test]
+    fn replacing_a_chunk_credits_the_blob_it_releases() {
+        store_chunk_for_model("m1", "c0", b"alpha".to_vec(), None).unwrap();
+        set_storage_capacity(Some(get_stored_bytes())).unwrap();
+
+        // Same size, different bytes: the old blob is freed by the write
+        store_chunk_for_model("m1", "c0", b"omega".to_vec(), None).unwrap();
+        assert_eq!(get_chunk_for_model("m1", "c0").unwrap(), b"omega");
+        assert_eq!(get_stored_bytes(), b"omega".len() as u64);
+    }
+
+    #[test]
+    fn replacing_a_shared_chunk_gets_no_credit() {
+        store_chunk_for_model("m1", "c0", b"alpha".to_vec(), None).unwrap();
+        store_chunk_for_model("m2", "c0", b"alpha".to_vec(), None).unwrap();
+        set_storage_capacity(Some(get_stored_bytes())).unwrap();
+
+        // m2 still holds the old blob, so nothing is freed
+        assert!(matches!(
+            store_chunk_for_model("m1", "c0", b"omega".to_vec(), None),
+            Err(ModelError::StorageFull)
+        ));
+        assert_eq!(get_chunk_for_model("m1", "c0").unwrap(), b"alpha");
+    }
+
+    #[test]
+    fn access_concentration_is_zero_for_an_empty_or_single_model_catalog() {
+        assert_eq!(get_access_concentration(), 0.0);
+        store_models_with_accesses(&[25]);
+        assert_eq!(get_access_concentration(), 0.0);
+    }
+
+    #[test]
+    fn uniform_access_has_near_zero_concentration() {
+        store_models_with_accesses(&[10; 20]);
+        assert!(get_access_concentration() < 0.01);
+    }
+
+    #[test]
+    fn skewed_access_has_near_one_concentration() {
+        let mut counts = vec![0; 99];
+        counts.push(500);
+        store_models_with_accesses(&counts);
+        assert!(get_access_concentration() > 0.98);
+    }
+
+    #[test]
+    fn query_models_applies_every_combination_of_filters() {
+        // (id, type, compression ratio, retention, original bytes, arch, perplexity delta)
+        let models = [
+            ("gptq-small", CompressionType::GPTQ, Some(4.0), 95.0, 4_000_000_000u64, "llama", Some(0.5)),
+            ("gptq-lossy", CompressionType::GPTQ, Some(8.0), 80.0, 8_000_000_000, "llama", Some(0.05)),
+            ("novaq-big", CompressionType::NOVAQ, Some(10.0), 99.0, 100_000_000_000, "llama", Some(2.0)),
+            ("awq-mistral", CompressionType::AWQ, Some(3.0), 90.0, 3_000_000_000, "mistral", None),
+            ("plain", CompressionType::Uncompressed, None, 0.0, 0, "llama", None),
+        ];
+        for (id, compression_type, ratio, retention, original_bytes, arch, delta) in &models {
+            let mut model = manifest(id, ModelState::Active);
+            model.compression_type = compression_type.clone();
+            model.compression = ratio.map(|compression_ratio| CompressionInfo {
+                compression_ratio,
+                capability_retention: Some(*retention),
+                bits_per_weight: None,
+                original_size_bytes: Some(*original_bytes),
+            });
+            store_manifest(id, &model).unwrap();
+            store_model_meta(id, &meta("llama", arch)).unwrap();
+            if let Some(delta) = delta {
+                let report = NOVAQVerificationReport {
+                    bit_accuracy: 99.0,
+                    perplexity_delta: Some(*delta),
+                    max_layer_error: None,
+                    per_subspace_mse: None,
+                };
+                store_quality_report(id, &report).unwrap();
+            }
+        }
+
+        // Which models each filter on its own lets through
+        let passes: [&[&str]; 6] = [
+            &["gptq-small", "gptq-lossy"],                // compression_type = GPTQ
+            &["gptq-small", "gptq-lossy", "novaq-big"],   // min_compression_ratio = 3.5
+            &["gptq-small", "novaq-big"],                 // min_capability_retention = 92
+            &["gptq-small", "gptq-lossy", "awq-mistral"], // max_size_mb = 2000
+            &["gptq-small", "gptq-lossy", "novaq-big", "plain"], // architecture = llama
+            &["gptq-small", "novaq-big"],                 // min_perplexity_delta = 0.1
+        ];
+
+        for mask in 0u32..(1 << passes.len()) {
+            let set = |bit: u32| mask & (1 << bit) != 0;
+            let query = ModelQuery {
+                compression_type: set(0).then_some(CompressionType::GPTQ),
+                min_compression_ratio: set(1).then_some(3.5),
+                min_capability_retention: set(2).then_some(92.0),
+                max_size_mb: set(3).then_some(2000.0),
+                architecture: set(4).then(|| "llama".to_string()),
+                min_perplexity_delta: set(5).then_some(0.1),
+            };
+
+            let mut found: Vec<String> = query_models(&query).into_iter().map(|m| m.model_id.0).collect();
+            found.sort();
+            let mut expected: Vec<String> = models.iter()
+                .map(|model| model.0)
+                .filter(|id| (0..passes.len()).all(|bit| !set(bit as u32) || passes[bit].contains(id)))
+                .map(str::to_string)
+                .collect();
+            expected.sort();
+            assert_eq!(found, expected, "filters set: {:06b}", mask);
+        }
+    }
+
+    #[test]
+    fn size_saved_agrees_with_get_size_mb() {
+        // 8 GB at 4x compression is 2 GB stored, 6 GB saved
+        let model = compressed("m1", CompressionType::GPTQ, 4.0, 8_000_000_000);
+        assert_eq!(model.get_size_mb(), Some(2000.0));
+        store_manifest("m1", &model).unwrap();
+
+        let stats = get_global_stats().unwrap();
+        let saved_gb = (model.original_size_mb() - model.get_size_mb().unwrap()) / 1000.0;
+        assert!((stats.total_size_saved_gb - saved_gb).abs() < 1e-3);
+        assert!((stats.total_size_saved_gb - 6.0).abs() < 1e-3);
+    }
+
+    #[test]
+    fn family_and_arch_queries_cover_several_families() {
+        let models = [
+            ("llama-7b", "llama", "transformer"),
+            ("llama-13b", "Llama", "transformer"),
+            ("mistral-7b", "mistral", "transformer"),
+            ("mamba-3b", "mamba", "ssm"),
+        ];
+        for (id, family, arch) in models {
+            store_model_meta(id, &meta(family, arch)).unwrap();
+        }
+        let ids = |found: Vec<ModelId>| {
+            let mut ids: Vec<String> = found.into_iter().map(|id| id.0).collect();
+            ids.sort();
+            ids
+        };
+
+        // Family lookups ignore case
+        assert_eq!(ids(query_models_by_family("LLAMA")), vec!["llama-13b", "llama-7b"]);
+        assert_eq!(ids(query_models_by_family("mistral")), vec!["mistral-7b"]);
+        assert_eq!(ids(query_models_by_family("mamba")), vec!["mamba-3b"]);
+        assert!(query_models_by_family("phi").is_empty());
+        assert_eq!(ids(query_models_by_arch("transformer")), vec!["llama-13b", "llama-7b", "mistral-7b"]);
+        assert_eq!(ids(query_models_by_arch("ssm")), vec!["mamba-3b"]);
+
+        // Replacing the metadata moves the model between index entries
+        store_model_meta("mistral-7b", &meta("mixtral", "moe")).unwrap();
+        assert!(query_models_by_family("mistral").is_empty());
+        assert_eq!(ids(query_models_by_family("mixtral")), vec!["mistral-7b"]);
+        assert_eq!(ids(query_models_by_arch("moe")), vec!["mistral-7b"]);
+        assert_eq!(ids(query_models_by_arch("transformer")), vec!["llama-13b", "llama-7b"]);
+    }
+
+    #[test]
+    fn incremental_stats_match_a_full_recompute() {
+        let check = || {
+            let incremental = get_global_stats().unwrap();
+            let recomputed = compute_stats_totals().finish();
+            assert_eq!(incremental.total_models, recomputed.total_models);
+            assert_eq!(incremental.quantized_models, recomputed.quantized_models);
+            for (a, b) in [
+                (incremental.total_size_saved_gb, recomputed.total_size_saved_gb),
+                (incremental.total_energy_saved, recomputed.total_energy_saved),
+                (incremental.average_compression_ratio, recomputed.average_compression_ratio),
+                (incremental.average_capability_retention, recomputed.average_capability_retention),
+            ] {
+                assert!((a - b).abs() < 1e-3, "incremental {} != recomputed {}", a, b);
+            }
+            incremental
+        };
+
+        store_manifest("gptq", &compressed("gptq", CompressionType::GPTQ, 4.0, 8_000_000_000)).unwrap();
+        store_manifest("awq", &compressed("awq", CompressionType::AWQ, 3.0, 3_000_000_000)).unwrap();
+        store_manifest("plain", &manifest("plain", ModelState::Pending)).unwrap();
+        assert_eq!(check().quantized_models, 2);
+
+        // Overwriting swaps the old contribution for the new one
+        store_manifest("awq", &compressed("awq", CompressionType::AWQ, 6.0, 3_000_000_000)).unwrap();
+        let mut deprecated = compressed("gptq", CompressionType::GPTQ, 4.0, 8_000_000_000);
+        deprecated.state = ModelState::Deprecated;
+        store_manifest("gptq", &deprecated).unwrap();
+        assert!((check().average_compression_ratio - 5.0).abs() < 1e-3);
+
+        delete_model_records(&get_manifest("awq").unwrap()).unwrap();
+        let stats = check();
+        assert_eq!(stats.total_models, 2);
+        assert_eq!(stats.quantized_models, 1);
+
+        // recompute_stats rebuilds the same totals it persists
+        assert_eq!(recompute_stats().unwrap().total_models, stats.total_models);
+        check();
+    }
+
+    #[test]
+    fn size_inconsistencies_compare_decompressed_length_to_the_manifest() {
+        for id in ["intact", "padded", "missing"] {
+            let mut model = manifest(id, ModelState::Active);
+            model.chunks[0].size = 64;
+            store_manifest(id, &model).unwrap();
+        }
+        // Both chunks compress well, so neither is stored at its manifest size
+        let (codec, stored) = store_chunk_for_model("intact", "chunk-0", vec![b'a'; 64], Some(ChunkCodec::Lz4)).unwrap();
+        assert!(matches!(codec, Some(ChunkCodec::Lz4)) && stored < 64);
+        let (codec, _) = store_chunk_for_model("padded", "chunk-0", vec![b'b'; 80], Some(ChunkCodec::Lz4)).unwrap();
+        assert!(matches!(codec, Some(ChunkCodec::Lz4)));
+
+        // Only the chunk whose decompressed bytes disagree is reported
+        assert_eq!(find_size_inconsistencies(), vec![("padded".to_string(), "chunk-0".to_string())]);
+    }
+}
@@ -0,0 +1,148 @@
+use crate::domain::*;
+use crate::services::{storage, validation};
+use sha2::{Digest, Sha256};
+
+/// Staged sessions not finished within a day are discarded
+pub const UPLOAD_SESSION_TTL_NS: u64 = 24 * 60 * 60 * 1_000_000_000;
+
+/// Open a staged upload. The manifest is fixed for the life of the session;
+/// chunks are then sent one message at a time with `put_chunk`.
+pub fn begin(
+    model_id: ModelId,
+    manifest: ModelManifest,
+    meta: ModelMeta,
+    signature: Option<String>,
+    owner: String,
+    now: u64,
+) -> Result<UploadSession, String> {
+    if manifest.model_id.0 != model_id.0 {
+        return Err("Manifest model_id does not match upload model_id".to_string());
+    }
+    if manifest.chunks.is_empty() {
+        return Err("Manifest must list at least one chunk".to_string());
+    }
+
+    expire_sessions(now);
+
+    let session = UploadSession {
+        session_id: storage::next_upload_session_id()
+            .map_err(|e| format!("Session id allocation failed: {:?}", e))?,
+        model_id,
+        manifest,
+        meta,
+        signature,
+        owner,
+        created_at: now,
+        expires_at: now.saturating_add(UPLOAD_SESSION_TTL_NS),
+        received_chunks: Vec::new(),
+    };
+    storage::store_upload_session(&session)
+        .map_err(|e| format!("Session store error: {:?}", e))?;
+
+    Ok(session)
+}
+
+/// Stage one chunk. Re-sending a chunk replaces it, so an interrupted
+/// upload can be resumed from whatever `received_chunks` reports.
+pub fn put_chunk(session_id: &str, chunk: ChunkData, caller: &str, now: u64) -> Result<UploadSession, String> {
+    let mut session = open_session(session_id, caller, now)?;
+
+    let expected = session.manifest.chunks.iter()
+        .find(|c| c.id == chunk.chunk_id)
+        .ok_or_else(|| format!("Chunk {} is not listed in the manifest", chunk.chunk_id))?;
+
+    validation::validate_chunk_integrity(&chunk)
+        .map_err(|e| format!("Chunk {} rejected: {}", chunk.chunk_id, e))?;
+
+    if expected.size != chunk.data.len() as u64 {
+        return Err(format!("Chunk size mismatch for {}: {} != {}",
+            chunk.chunk_id, expected.size, chunk.data.len()));
+    }
+    let calculated_hash = hex::encode(Sha256::digest(&chunk.data));
+    if expected.sha256 != calculated_hash {
+        return Err(format!("Hash mismatch for chunk {}: {} != {}",
+            chunk.chunk_id, expected.sha256, calculated_hash));
+    }
+
+    storage::store_staged_chunk(session_id, &chunk.chunk_id, chunk.data);
+    if !session.received_chunks.contains(&chunk.chunk_id) {
+        session.received_chunks.push(chunk.chunk_id);
+        storage::store_upload_session(&session)
+            .map_err(|e| format!("Session store error: {:?}", e))?;
+    }
+
+    Ok(session)
+}
+
+/// Assemble the staged chunks into a full upload, in manifest order.
+/// The session is left in place; the caller discards it once committed.
+pub fn assemble(session_id: &str, caller: &str, now: u64) -> Result<ModelUpload, String> {
+    let session = open_session(session_id, caller, now)?;
+
+    let missing: Vec<&str> = session.manifest.chunks.iter()
+        .filter(|c| !session.received_chunks.contains(&c.id))
+        .map(|c| c.id.as_str())
+        .collect();
+    if !missing.is_empty() {
+        return Err(format!("Missing {} chunks: {}", missing.len(), missing.join(", ")));
+    }
+
+    let mut chunks = Vec::with_capacity(session.manifest.chunks.len());
+    for info in &session.manifest.chunks {
+        let data = storage::get_staged_chunk(session_id, &info.id)
+            .map_err(|_| format!("Staged chunk {} missing", info.id))?;
+        chunks.push(ChunkData { chunk_id: info.id.clone(), data });
+    }
+
+    Ok(ModelUpload {
+        model_id: session.model_id,
+        manifest: session.manifest,
+        meta: session.meta,
+        chunks,
+        signature: session.signature,
+        verification_report: None,
+        idempotency_key: None,
+        overwrite: None,
+        compress_chunks: None,
+    })
+}
+
+pub fn cancel(session_id: &str, caller: &str) -> Result<(), String> {
+    let session = storage::get_upload_session(session_id)
+        .map_err(|_| "Upload session not found".to_string())?;
+    if session.owner != caller {
+        return Err("Upload session belongs to another caller".to_string());
+    }
+
+    storage::remove_upload_session(session_id);
+    Ok(())
+}
+
+pub fn discard(session_id: &str) {
+    storage::remove_upload_session(session_id);
+}
+
+/// Drop every session past its TTL. Returns how many were removed.
+pub fn expire_sessions(now: u64) -> u32 {
+    let mut expired = 0;
+    for session in storage::list_upload_sessions() {
+        if session.expires_at <= now {
+            storage::remove_upload_session(&session.session_id);
+            expired += 1;
+        }
+    }
+    expired
+}
+
+fn open_session(session_id: &str, caller: &str, now: u64) -> Result<UploadSession, String> {
+    let session = storage::get_upload_session(session_id)
+        .map_err(|_| "Upload session not found".to_string())?;
+    if session.owner != caller {
+        return Err("Upload session belongs to another caller".to_string());
+    }
+    if session.expires_at <= now {
+        storage::remove_upload_session(session_id);
+        return Err("Upload session expired".to_string());
+    }
+    Ok(session)
+}
@@ -1,9 +1,11 @@
 use crate::domain::*;
+use ed25519_dalek::{Signature, VerifyingKey};
 use sha2::{Digest, Sha256};
 
 pub fn validate_chunk_integrity(chunk: &ChunkData) -> Result<(), String> {
-    if chunk.data.len() > 2 * 1024 * 1024 {
-        return Err("Chunk exceeds 2MiB size limit".to_string());
+    let max_chunk_size = crate::services::storage::get_max_chunk_size();
+    if chunk.data.len() as u64 > max_chunk_size {
+        return Err(format!("Chunk exceeds the {} byte size limit", max_chunk_size));
     }
 
     if chunk.data.is_empty() {
@@ -48,14 +50,46 @@ pub fn calculate_manifest_digest(manifest: &ModelManifest) -> String {
     // Hash all chunk information
     for chunk in &manifest.chunks {
         hasher.update(chunk.id.as_bytes());
-        hasher.update(&chunk.offset.to_le_bytes());
-        hasher.update(&chunk.size.to_le_bytes());
+        hasher.update(chunk.offset.to_le_bytes());
+        hasher.update(chunk.size.to_le_bytes());
         hasher.update(chunk.sha256.as_bytes());
     }
     
     hex::encode(hasher.finalize())
 }
 
+/// Reject manifests whose claimed digest does not match the chunk infos
+pub fn validate_manifest_digest(manifest: &ModelManifest) -> Result<(), String> {
+    let computed = calculate_manifest_digest(manifest);
+    if manifest.digest != computed {
+        return Err(format!("Manifest digest mismatch: {} != {}", manifest.digest, computed));
+    }
+
+    Ok(())
+}
+
+/// Chunk infos sorted by offset, rejecting layouts that do not tile
+/// [0, total) exactly: any gap or overlap is an error
+pub fn ordered_chunks(manifest: &ModelManifest) -> Result<Vec<ChunkInfo>, String> {
+    let mut by_offset = manifest.chunks.clone();
+    by_offset.sort_by_key(|c| c.offset);
+    let mut expected_offset = 0u64;
+    for chunk in &by_offset {
+        if chunk.offset > expected_offset {
+            return Err(format!("Gap before chunk {}: expected offset {}, got {}",
+                chunk.id, expected_offset, chunk.offset));
+        }
+        if chunk.offset < expected_offset {
+            return Err(format!("Chunk {} overlaps the previous chunk: expected offset {}, got {}",
+                chunk.id, expected_offset, chunk.offset));
+        }
+        expected_offset = chunk.offset.checked_add(chunk.size)
+            .ok_or_else(|| format!("Chunk {} offset overflows", chunk.id))?;
+    }
+
+    Ok(by_offset)
+}
+
 pub fn validate_model_meta(meta: &ModelMeta) -> Result<(), String> {
     if meta.family.is_empty() {
         return Err("Model family cannot be empty".to_string());
@@ -74,4 +108,280 @@ pub fn validate_model_meta(meta: &ModelMeta) -> Result<(), String> {
     }
 
     Ok(())
-}
\ No newline at end of file
+}
+
+pub fn validate_novaq_model(model: &NOVAQModel) -> Result<(), String> {
+    let config = &model.config;
+
+    if config.target_bits.is_nan() || config.target_bits <= 0.0 || config.target_bits > 16.0 {
+        return Err(format!("Target bits must be in (0, 16], got {}", config.target_bits));
+    }
+
+    if config.num_subspaces == 0 {
+        return Err("Number of subspaces must be greater than 0".to_string());
+    }
+
+    if config.codebook_size_l1 == 0 || config.codebook_size_l2 == 0 {
+        return Err("Codebook sizes must be greater than 0".to_string());
+    }
+
+    let min_ratio = crate::services::storage::get_min_compression_ratio();
+    if !model.compression_ratio.is_finite() || model.compression_ratio <= min_ratio {
+        return Err(format!("Compression ratio must be greater than {}, got {}; the model does not compress enough to be labelled NOVAQ",
+            min_ratio, model.compression_ratio));
+    }
+
+    if !(0.0..=100.0).contains(&model.bit_accuracy) {
+        return Err(format!("Bit accuracy must be within [0, 100], got {}", model.bit_accuracy));
+    }
+
+    if model.vector_codebooks.len() != config.num_subspaces {
+        return Err(format!("Codebook count mismatch: {} codebooks for {} subspaces",
+            model.vector_codebooks.len(), config.num_subspaces));
+    }
+
+    validate_weight_shapes(&model.weight_shapes)
+}
+
+/// Tensor names are used as lookup keys during reconstruction, so each must
+/// be non-empty and unique, and no dimension may be zero
+pub fn validate_weight_shapes(weight_shapes: &[(String, Vec<usize>)]) -> Result<(), String> {
+    let mut seen = std::collections::HashSet::new();
+    for (name, shape) in weight_shapes {
+        if name.trim().is_empty() {
+            return Err("Weight shape names cannot be empty".to_string());
+        }
+        if !seen.insert(name.as_str()) {
+            return Err(format!("Duplicate weight shape name {}", name));
+        }
+        if shape.contains(&0) {
+            return Err(format!("Weight shape {} has a zero dimension: {:?}", name, shape));
+        }
+    }
+    Ok(())
+}
+
+/// Reject quality reports with non-finite or negative error figures, or a
+/// per-subspace MSE list that does not match the model's subspaces
+pub fn validate_verification_report(report: &NOVAQVerificationReport, model: &NOVAQModel) -> Result<(), String> {
+    if !(0.0..=100.0).contains(&report.bit_accuracy) {
+        return Err(format!("Bit accuracy must be within [0, 100], got {}", report.bit_accuracy));
+    }
+    if let Some(delta) = report.perplexity_delta {
+        if !delta.is_finite() {
+            return Err(format!("Perplexity delta must be finite, got {}", delta));
+        }
+    }
+    if let Some(error) = report.max_layer_error {
+        if !error.is_finite() || error < 0.0 {
+            return Err(format!("Max layer error must be finite and non-negative, got {}", error));
+        }
+    }
+    if let Some(mse) = &report.per_subspace_mse {
+        if mse.len() != model.config.num_subspaces {
+            return Err(format!("Expected {} per-subspace MSE values, got {}",
+                model.config.num_subspaces, mse.len()));
+        }
+        if let Some(value) = mse.iter().find(|v| !v.is_finite() || **v < 0.0) {
+            return Err(format!("Per-subspace MSE must be finite and non-negative, got {}", value));
+        }
+    }
+    Ok(())
+}
+
+/// Check that every quantization index addresses an existing centroid.
+/// Rows `0..num_subspaces` index the level-1 codebook of that subspace;
+/// an optional second set of `num_subspaces` rows holds level-2 indices,
+/// bounded by `codebook_size_l2`. All rows must have the same length and,
+/// when shapes are known, cover exactly the weights in `weight_shapes`.
+pub fn validate_quantization_indices(model: &NOVAQModel) -> Result<(), String> {
+    let config = &model.config;
+    let subspaces = config.num_subspaces;
+    let rows = model.quantization_indices.len();
+    if rows != subspaces && rows != 2 * subspaces {
+        return Err(format!("Expected {} or {} index rows for {} subspaces, got {}",
+            subspaces, 2 * subspaces, subspaces, rows));
+    }
+
+    let row_len = model.quantization_indices.first().map_or(0, |row| row.len());
+    for (row, indices) in model.quantization_indices.iter().enumerate() {
+        let subspace = row % subspaces;
+        if indices.len() != row_len {
+            return Err(format!("Subspace {}: index row {} has {} entries, expected {}",
+                subspace, row, indices.len(), row_len));
+        }
+
+        let bound = if row < subspaces {
+            let entries = model.vector_codebooks.get(subspace).map_or(0, |codebook| codebook.len());
+            config.codebook_size_l1.min(entries)
+        } else {
+            config.codebook_size_l2
+        };
+        if let Some(&index) = indices.iter().find(|&&index| index as usize >= bound) {
+            return Err(format!("Subspace {}: index {} in row {} is out of bounds (< {})",
+                subspace, index, row, bound));
+        }
+    }
+
+    // Each level-1 index stands for one centroid's worth of weights
+    let total_weights: u64 = model.weight_shapes.iter()
+        .map(|(_, shape)| shape.iter().fold(1u64, |acc, &dim| acc.saturating_mul(dim as u64)))
+        .fold(0u64, |acc, n| acc.saturating_add(n));
+    let centroid_dims: u64 = model.vector_codebooks.iter()
+        .map(|codebook| codebook.first().map_or(0, |centroid| centroid.len() as u64))
+        .sum();
+    if total_weights > 0 && centroid_dims > 0 {
+        let covered = (row_len as u64).saturating_mul(centroid_dims);
+        if covered != total_weights {
+            return Err(format!("Indices cover {} weights but weight_shapes describe {}",
+                covered, total_weights));
+        }
+    }
+
+    Ok(())
+}
+
+pub fn parse_public_key(public_key: &[u8]) -> Result<VerifyingKey, String> {
+    let bytes: [u8; 32] = public_key
+        .try_into()
+        .map_err(|_| "Public key must be 32 bytes".to_string())?;
+    VerifyingKey::from_bytes(&bytes).map_err(|_| "Invalid Ed25519 public key".to_string())
+}
+
+/// Verify a hex-encoded Ed25519 signature over the raw bytes of the
+/// digest produced by `calculate_manifest_digest`
+pub fn verify_manifest_signature(
+    public_key: &[u8],
+    manifest: &ModelManifest,
+    signature_hex: &str,
+) -> Result<(), String> {
+    let key = parse_public_key(public_key)?;
+
+    let signature_bytes = hex::decode(signature_hex)
+        .map_err(|_| "Signature must be hex-encoded".to_string())?;
+    let signature = Signature::from_slice(&signature_bytes)
+        .map_err(|_| "Signature must be 64 bytes".to_string())?;
+
+    let message = hex::decode(calculate_manifest_digest(manifest)).unwrap_or_default();
+    key.verify_strict(&message, &signature)
+        .map_err(|_| "Signature does not match manifest digest".to_string())
+}
+
+/// Largest per-centroid difference still treated as numerically equal
+pub const REPRODUCIBILITY_TOLERANCE: f32 = 1e-4;
+
+/// Largest absolute difference between corresponding codebook values, or
+/// `None` if the codebooks do not have the same shape
+pub fn max_codebook_delta(a: &NOVAQModelCandid, b: &NOVAQModelCandid) -> Option<f32> {
+    if a.vector_codebooks.len() != b.vector_codebooks.len() {
+        return None;
+    }
+
+    let mut max_delta = 0.0f32;
+    for (book_a, book_b) in a.vector_codebooks.iter().zip(&b.vector_codebooks) {
+        if book_a.len() != book_b.len() {
+            return None;
+        }
+        for (centroid_a, centroid_b) in book_a.iter().zip(book_b) {
+            if centroid_a.len() != centroid_b.len() {
+                return None;
+            }
+            for (x, y) in centroid_a.iter().zip(centroid_b) {
+                max_delta = max_delta.max((x - y).abs());
+            }
+        }
+    }
+    Some(max_delta)
+}
+
+/// Structural checks on a NOVAQ model decoded from stored chunks
+pub fn verify_novaq_structure(model: &NOVAQModelCandid) -> Result<(), String> {
+    let config = &model.config;
+
+    if model.vector_codebooks.len() != config.num_subspaces as usize {
+        return Err(format!("Codebook count mismatch: {} codebooks for {} subspaces",
+            model.vector_codebooks.len(), config.num_subspaces));
+    }
+
+    for (i, codebook) in model.vector_codebooks.iter().enumerate() {
+        if codebook.is_empty() || codebook.len() > config.codebook_size_l1 as usize {
+            return Err(format!("Codebook {} has {} entries, expected 1..={}",
+                i, codebook.len(), config.codebook_size_l1));
+        }
+        let dim = codebook[0].len();
+        if codebook.iter().any(|centroid| centroid.len() != dim) {
+            return Err(format!("Codebook {} has centroids of differing dimension", i));
+        }
+    }
+
+    if model.quantization_indices.is_empty() {
+        return Err("Model has no quantization indices".to_string());
+    }
+
+    for (i, indices) in model.quantization_indices.iter().enumerate() {
+        if let Some(&index) = indices.iter().find(|&&index| index as u32 >= config.codebook_size_l1) {
+            return Err(format!("Quantization index {} in row {} exceeds codebook size {}",
+                index, i, config.codebook_size_l1));
+        }
+    }
+
+    if !(0.0..=100.0).contains(&model.bit_accuracy) {
+        return Err(format!("Bit accuracy must be within [0, 100], got {}", model.bit_accuracy));
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn novaq_model(weight_shapes: Vec<(String, Vec<usize>)>) -> NOVAQModel {
+        NOVAQModel {
+            config: NOVAQConfig {
+                target_bits: 4.0,
+                num_subspaces: 2,
+                codebook_size_l1: 4,
+                codebook_size_l2: 4,
+                outlier_threshold: 0.01,
+                teacher_model_path: None,
+                refinement_iterations: 1,
+                kl_weight: 1.0,
+                cosine_weight: 1.0,
+                learning_rate: 0.01,
+                seed: 7,
+            },
+            compression_ratio: 8.0,
+            bit_accuracy: 99.0,
+            vector_codebooks: vec![vec![vec![0.5; 4]; 4]; 2],
+            quantization_indices: vec![vec![0, 1, 2, 3]; 2],
+            weight_shapes,
+            normalization_metadata: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn duplicate_tensor_names_are_rejected() {
+        let unique = novaq_model(vec![
+            ("attn.weight".to_string(), vec![4, 4]),
+            ("mlp.weight".to_string(), vec![4, 4]),
+        ]);
+        assert!(validate_novaq_model(&unique).is_ok());
+
+        let duplicated = novaq_model(vec![
+            ("attn.weight".to_string(), vec![4, 4]),
+            ("attn.weight".to_string(), vec![4, 4]),
+        ]);
+        assert_eq!(
+            validate_novaq_model(&duplicated).unwrap_err(),
+            "Duplicate weight shape name attn.weight"
+        );
+    }
+
+    #[test]
+    fn empty_names_and_zero_dimensions_are_rejected() {
+        assert!(validate_weight_shapes(&[(" ".to_string(), vec![4])]).is_err());
+        let err = validate_weight_shapes(&[("mlp.weight".to_string(), vec![4, 0])]).unwrap_err();
+        assert!(err.contains("mlp.weight"), "{}", err);
+    }
+}